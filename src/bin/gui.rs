@@ -1,15 +1,34 @@
 use clap::Parser;
+use sui_summary_explorer::{LoadOptions, ParseMode};
 
 #[derive(Parser)]
 struct Args {
+    /// Summaries folder or archive, or a folder of compiled `.mv` modules
     folder: Option<String>,
+    #[arg(long)]
+    lenient: bool,
+    /// Keep a `<folder>.summary-cache` file next to each folder to speed up
+    /// later loads
+    #[arg(long)]
+    cache: bool,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    sui_summary_explorer::gui::main(args.folder).await?;
+    let mode = if args.lenient {
+        ParseMode::Lenient
+    } else {
+        ParseMode::Strict
+    };
+
+    let options = LoadOptions {
+        mode,
+        cache: args.cache,
+    };
+
+    sui_summary_explorer::gui::main(args.folder, options).await?;
 
     Ok(())
 }
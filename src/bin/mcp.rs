@@ -1,28 +1,93 @@
-use clap::Parser;
-use rmcp::transport::streamable_http_server::{
-    StreamableHttpService, session::local::LocalSessionManager,
+use clap::{Parser, ValueEnum};
+use rmcp::{
+    ServiceExt,
+    transport::{
+        stdio,
+        streamable_http_server::{StreamableHttpService, session::local::LocalSessionManager},
+    },
 };
-use sui_summary_explorer::service::SuiService;
+use sui_summary_explorer::{
+    LoadOptions, ParseMode,
+    reload::{self, LivePackageTree},
+    service::SuiService,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Transport {
+    /// Serve over stdin/stdout, for clients that spawn the server
+    Stdio,
+    /// Serve streamable HTTP on 127.0.0.1
+    Http,
+}
 
 #[derive(Parser)]
 struct Args {
+    #[arg(long, value_enum, default_value_t = Transport::Http)]
+    transport: Transport,
+    /// Only used by the http transport
     #[arg(short, long, default_value_t = 9393)]
     port: u16,
+    /// Can be given more than once to merge several summaries folders.
+    /// A folder of compiled `.mv` modules is read as bytecode.
     #[arg(short, long, default_value = "./package_summaries")]
-    summaries_folder: String,
+    summaries_folder: Vec<String>,
+    #[arg(long)]
+    lenient: bool,
+    #[arg(long)]
+    no_watch: bool,
+    /// Keep a `<folder>.summary-cache` file next to each folder to speed up
+    /// later loads
+    #[arg(long)]
+    cache: bool,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let pkg_path = std::path::Path::new(&args.summaries_folder);
-    let packages = sui_summary_explorer::PackageTree::new(&pkg_path)?;
+    let pkg_paths: Vec<std::path::PathBuf> = args.summaries_folder.iter().map(Into::into).collect();
+    let mode = if args.lenient {
+        ParseMode::Lenient
+    } else {
+        ParseMode::Strict
+    };
+    let options = LoadOptions {
+        mode,
+        cache: args.cache,
+    };
+    let packages = sui_summary_explorer::PackageTree::load_many(&pkg_paths, options)?;
 
     let bind_address = format!("127.0.0.1:{}", args.port);
 
-    println!("target folder: {}", args.summaries_folder);
-    println!("bind address: {}", bind_address);
+    for folder in &args.summaries_folder {
+        eprintln!("target folder: {}", folder);
+    }
+    if args.transport == Transport::Http {
+        eprintln!("bind address: {}", bind_address);
+    }
+    for diagnostic in packages.diagnostics() {
+        eprintln!("{}", diagnostic);
+    }
+    for issue in packages.validate() {
+        eprintln!("warning: {}", issue);
+    }
+
+    let packages = LivePackageTree::new(packages);
+    let _watcher = if args.no_watch {
+        None
+    } else {
+        Some(reload::spawn_reloader(
+            pkg_paths,
+            options,
+            packages.clone(),
+        )?)
+    };
+
+    if args.transport == Transport::Stdio {
+        let service = SuiService::new(packages).serve(stdio()).await?;
+        service.waiting().await?;
+        return Ok(());
+    }
 
     let service = StreamableHttpService::new(
         move || Ok(SuiService::new(packages.clone())),
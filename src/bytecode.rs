@@ -0,0 +1,579 @@
+use crate::{
+    cache::hash_bytes,
+    parser::{Diagnostic, ParseMode, ParsedSummaries, Result, SummaryError},
+};
+use move_binary_format::{
+    CompiledModule,
+    file_format::{
+        Ability, AbilitySet, DatatypeHandleIndex, FieldDefinition, IdentifierIndex, SignatureToken,
+        StructFieldInformation, Visibility,
+    },
+};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use move_model_2::summary;
+use move_symbol_pool::Symbol;
+use rayon::prelude::*;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const BYTECODE_EXT: &str = "mv";
+const BYTECODE_DIR: &str = "bytecode_modules";
+const DEPENDENCIES_DIR: &str = "dependencies";
+const ADDRESS_MAPPING_FILE: &str = "address_mapping.json";
+
+/// Whether `path` is a folder of compiled `.mv` modules rather than a
+/// `package_summaries` folder, e.g. `build/<pkg>/bytecode_modules`.
+pub fn is_bytecode_dir(path: &Path) -> bool {
+    path.is_dir()
+        && !path.join(ADDRESS_MAPPING_FILE).exists()
+        && collect_module_files(path).is_ok_and(|files| !files.is_empty())
+}
+
+/// Build summaries from compiled Move modules found anywhere below `path`.
+///
+/// Modules are grouped into packages by address and named the way summaries
+/// name them, see `package_name`. When folders of different names hold
+/// modules at the same address, as unpublished packages at `0x0` do, the root
+/// package of a build directory is loaded and the modules of the others are
+/// reported as `Diagnostic::SharedAddress`.
+///
+/// Bytecode carries no parameter or type parameter names, no doc comments and
+/// no attributes, so those are left empty.
+pub fn parse_bytecode<P: Into<PathBuf>>(pb: P, mode: ParseMode) -> Result<ParsedSummaries> {
+    let root: PathBuf = pb.into();
+    if !root.exists() {
+        return Err(SummaryError::MissingDirectory { path: root });
+    }
+    if !root.is_dir() {
+        return Err(SummaryError::NotADirectory { path: root });
+    }
+
+    let files = collect_module_files(&root)?;
+    let parsed: Vec<_> = files
+        .par_iter()
+        .map(|path| summarize_module_file(path))
+        .collect();
+
+    let mut diagnostics = Vec::new();
+    let mut file_hashes = BTreeMap::new();
+    let mut packages: BTreeMap<AccountAddress, summary::Package> = BTreeMap::new();
+    for (path, module) in files.iter().zip(parsed) {
+        let module = match module {
+            Ok((module, hash)) => {
+                let rel = path.strip_prefix(&root).unwrap_or(path);
+                file_hashes.insert(rel.to_path_buf(), hash);
+                module
+            }
+            Err(err) => match mode {
+                ParseMode::Strict => return Err(err),
+                ParseMode::Lenient => {
+                    diagnostics.push(Diagnostic::SkippedModule(err));
+                    continue;
+                }
+            },
+        };
+
+        // Unpublished packages all live at `0x0`, so modules of different
+        // packages can share an address and must not be merged
+        let name = package_name(path, &module.id.address);
+        let package = packages
+            .entry(module.id.address)
+            .or_insert_with(|| summary::Package {
+                name: Some(name),
+                modules: BTreeMap::new(),
+            });
+        if let Some(kept) = package.name
+            && kept != name
+        {
+            diagnostics.push(Diagnostic::SharedAddress {
+                address: module.id.address,
+                kept: kept.to_string(),
+                ignored: name.to_string(),
+                path: path.clone(),
+            });
+            continue;
+        }
+        package.modules.insert(module.id.name, module);
+    }
+
+    Ok(ParsedSummaries {
+        packages: summary::Packages { packages },
+        metadata: None,
+        diagnostics,
+        file_hashes,
+    })
+}
+
+/// All `.mv` files below `root`, those of the root package first and in path
+/// order otherwise, so that the root package wins an address clash.
+fn collect_module_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let unreadable = |source| SummaryError::Unreadable {
+            path: dir.clone(),
+            source,
+        };
+        for entry in fs::read_dir(&dir).map_err(unreadable)? {
+            let path = entry.map_err(unreadable)?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == BYTECODE_EXT) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort_by_cached_key(|path| {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        let dependency = rel
+            .components()
+            .any(|component| component.as_os_str() == DEPENDENCIES_DIR);
+        (dependency, path.clone())
+    });
+    Ok(files)
+}
+
+/// Named addresses of the framework packages, as summaries name them
+const FRAMEWORK_PACKAGES: &[(&str, &str)] = &[
+    ("0x1", "std"),
+    ("0x2", "sui"),
+    ("0x3", "sui_system"),
+    ("0xb", "bridge"),
+];
+
+/// Name a package the way summaries do: framework packages by their named
+/// address, so `MoveStdlib` becomes `std`, and other packages after the
+/// folder their modules are in, in snake case and skipping the
+/// `bytecode_modules` folder of a build directory.
+fn package_name(module_file: &Path, address: &AccountAddress) -> Symbol {
+    let framework = FRAMEWORK_PACKAGES.iter().find(|(hex, _)| {
+        AccountAddress::from_hex_literal(hex).is_ok_and(|framework| framework == *address)
+    });
+    if let Some((_, name)) = framework {
+        return Symbol::from(*name);
+    }
+    let mut dir = module_file.parent();
+    if dir
+        .and_then(Path::file_name)
+        .is_some_and(|name| name == BYTECODE_DIR)
+    {
+        dir = dir.and_then(Path::parent);
+    }
+    dir.and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+        .map(|name| Symbol::from(snake_case(name)))
+        .unwrap_or_else(|| Symbol::from(address.to_hex_literal()))
+}
+
+/// `MyApp` and `my-app` to `my_app`
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let mut after_word_char = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if after_word_char {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            after_word_char = false;
+        } else if c == '-' || c == ' ' {
+            out.push('_');
+            after_word_char = false;
+        } else {
+            out.push(c);
+            after_word_char = c.is_ascii_lowercase() || c.is_ascii_digit();
+        }
+    }
+    out
+}
+
+/// The summary of a module file and the content hash of the file.
+fn summarize_module_file(path: &Path) -> Result<(summary::Module, u64)> {
+    let bytes = fs::read(path).map_err(|source| SummaryError::Unreadable {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let module = CompiledModule::deserialize_with_defaults(&bytes).map_err(|err| {
+        SummaryError::InvalidBytecode {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        }
+    })?;
+    Ok((module_summary(&module), hash_bytes(&bytes)))
+}
+
+fn module_summary(module: &CompiledModule) -> summary::Module {
+    let functions = module
+        .function_defs()
+        .iter()
+        .enumerate()
+        .map(|(index, def)| {
+            let handle = module.function_handle_at(def.function);
+            let function = summary::Function {
+                index: index as u64,
+                doc: None,
+                attributes: Vec::new(),
+                visibility: visibility(def.visibility),
+                entry: def.is_entry,
+                macro_: None,
+                type_parameters: handle
+                    .type_parameters
+                    .iter()
+                    .map(|constraints| tparam(*constraints))
+                    .collect(),
+                parameters: module
+                    .signature_at(handle.parameters)
+                    .0
+                    .iter()
+                    .map(|token| summary::Parameter {
+                        name: None,
+                        type_: summary_type(module, token),
+                    })
+                    .collect(),
+                return_: module
+                    .signature_at(handle.return_)
+                    .0
+                    .iter()
+                    .map(|token| summary_type(module, token))
+                    .collect(),
+            };
+            (identifier(module, handle.name), function)
+        })
+        .collect();
+
+    let structs = module
+        .struct_defs()
+        .iter()
+        .enumerate()
+        .map(|(index, def)| {
+            let fields = match &def.field_information {
+                StructFieldInformation::Native => &[][..],
+                StructFieldInformation::Declared(fields) => &fields[..],
+            };
+            let (name, abilities, type_parameters) = datatype_header(module, def.struct_handle);
+            let struct_ = summary::Struct {
+                index: index as u64,
+                doc: None,
+                attributes: Vec::new(),
+                abilities,
+                type_parameters,
+                fields: fields_summary(module, fields),
+            };
+            (name, struct_)
+        })
+        .collect();
+
+    let enums = module
+        .enum_defs()
+        .iter()
+        .enumerate()
+        .map(|(index, def)| {
+            let (name, abilities, type_parameters) = datatype_header(module, def.enum_handle);
+            let variants = def
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    let variant_summary = summary::Variant {
+                        index: index as u64,
+                        doc: None,
+                        fields: fields_summary(module, &variant.fields),
+                    };
+                    (identifier(module, variant.variant_name), variant_summary)
+                })
+                .collect();
+            let enum_ = summary::Enum {
+                index: index as u64,
+                doc: None,
+                attributes: Vec::new(),
+                abilities,
+                type_parameters,
+                variants,
+            };
+            (name, enum_)
+        })
+        .collect();
+
+    summary::Module {
+        id: module_id(&module.self_id()),
+        doc: None,
+        attributes: Vec::new(),
+        immediate_dependencies: module
+            .immediate_dependencies()
+            .iter()
+            .map(module_id)
+            .collect(),
+        functions,
+        structs,
+        enums,
+    }
+}
+
+/// Name, abilities and type parameters of a struct or enum
+fn datatype_header(
+    module: &CompiledModule,
+    handle: DatatypeHandleIndex,
+) -> (Symbol, summary::AbilitySet, Vec<summary::DatatypeTParam>) {
+    let handle = module.datatype_handle_at(handle);
+    let type_parameters = handle
+        .type_parameters
+        .iter()
+        .map(|tparam_| summary::DatatypeTParam {
+            phantom: tparam_.is_phantom,
+            tparam: tparam(tparam_.constraints),
+        })
+        .collect();
+    (
+        identifier(module, handle.name),
+        abilities(handle.abilities),
+        type_parameters,
+    )
+}
+
+fn fields_summary(module: &CompiledModule, fields: &[FieldDefinition]) -> summary::Fields {
+    let named = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let field_summary = summary::Field {
+                index: index as u64,
+                doc: None,
+                type_: summary_type(module, &field.signature.0),
+            };
+            (identifier(module, field.name), field_summary)
+        })
+        .collect();
+    // The compiler names positional fields `pos0`, `pos1`, ...
+    let positional_fields = !fields.is_empty()
+        && fields
+            .iter()
+            .enumerate()
+            .all(|(i, field)| module.identifier_at(field.name).as_str() == format!("pos{}", i));
+    summary::Fields {
+        positional_fields,
+        fields: named,
+    }
+}
+
+fn identifier(module: &CompiledModule, index: IdentifierIndex) -> Symbol {
+    Symbol::from(module.identifier_at(index).as_str())
+}
+
+fn tparam(constraints: AbilitySet) -> summary::TParam {
+    summary::TParam {
+        name: None,
+        constraints: abilities(constraints),
+    }
+}
+
+fn abilities(set: AbilitySet) -> summary::AbilitySet {
+    summary::AbilitySet(
+        set.into_iter()
+            .map(|ability| match ability {
+                Ability::Copy => summary::Ability::Copy,
+                Ability::Drop => summary::Ability::Drop,
+                Ability::Store => summary::Ability::Store,
+                Ability::Key => summary::Ability::Key,
+            })
+            .collect(),
+    )
+}
+
+fn visibility(visibility: Visibility) -> summary::Visibility {
+    match visibility {
+        Visibility::Public => summary::Visibility::Public,
+        Visibility::Friend => summary::Visibility::Friend,
+        Visibility::Private => summary::Visibility::Private,
+    }
+}
+
+fn module_id(id: &ModuleId) -> summary::ModuleId {
+    summary::ModuleId {
+        address: *id.address(),
+        name: Symbol::from(id.name().as_str()),
+    }
+}
+
+fn summary_type(module: &CompiledModule, token: &SignatureToken) -> summary::Type {
+    match token {
+        SignatureToken::Bool => summary::Type::Bool,
+        SignatureToken::U8 => summary::Type::U8,
+        SignatureToken::U16 => summary::Type::U16,
+        SignatureToken::U32 => summary::Type::U32,
+        SignatureToken::U64 => summary::Type::U64,
+        SignatureToken::U128 => summary::Type::U128,
+        SignatureToken::U256 => summary::Type::U256,
+        SignatureToken::Address => summary::Type::Address,
+        SignatureToken::Signer => summary::Type::Signer,
+        SignatureToken::Vector(inner) => {
+            summary::Type::Vector(Box::new(summary_type(module, inner)))
+        }
+        SignatureToken::Reference(inner) => {
+            summary::Type::Reference(false, Box::new(summary_type(module, inner)))
+        }
+        SignatureToken::MutableReference(inner) => {
+            summary::Type::Reference(true, Box::new(summary_type(module, inner)))
+        }
+        SignatureToken::TypeParameter(idx) => summary::Type::TypeParameter(*idx),
+        SignatureToken::Datatype(handle) => datatype(module, *handle, &[]),
+        SignatureToken::DatatypeInstantiation(inst) => {
+            let (handle, type_arguments) = &**inst;
+            datatype(module, *handle, type_arguments)
+        }
+    }
+}
+
+fn datatype(
+    module: &CompiledModule,
+    handle_idx: DatatypeHandleIndex,
+    type_arguments: &[SignatureToken],
+) -> summary::Type {
+    let handle = module.datatype_handle_at(handle_idx);
+    let defining_module = module.module_id_for_handle(module.module_handle_at(handle.module));
+    let type_arguments = type_arguments
+        .iter()
+        .zip(
+            handle
+                .type_parameters
+                .iter()
+                .map(|tparam| tparam.is_phantom)
+                .chain(std::iter::repeat(false)),
+        )
+        .map(|(argument, phantom)| summary::TypeArgument {
+            phantom,
+            argument: summary_type(module, argument),
+        })
+        .collect();
+    summary::Type::Datatype(Box::new(summary::Datatype {
+        module: module_id(&defining_module),
+        name: identifier(module, handle.name),
+        type_arguments,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// A version 6 compiled module with no members, `module <address>::<name>`:
+    /// module handles, identifiers and addresses tables, followed by the
+    /// index of the module's own handle.
+    fn compiled_module(address: u8, name: &str) -> Vec<u8> {
+        let identifiers_len = 1 + name.len() as u8;
+        let mut bytes = vec![0xA1, 0x1C, 0xEB, 0x0B, 6, 0, 0, 0, 3];
+        bytes.extend([0x1, 0, 2]);
+        bytes.extend([0x7, 2, identifiers_len]);
+        bytes.extend([0x8, 2 + identifiers_len, 32]);
+        bytes.extend([0, 0]);
+        bytes.push(name.len() as u8);
+        bytes.extend(name.as_bytes());
+        bytes.extend([0; 31]);
+        bytes.push(address);
+        bytes.push(0);
+        bytes
+    }
+
+    fn address(hex: &str) -> AccountAddress {
+        AccountAddress::from_hex_literal(hex).unwrap()
+    }
+
+    fn write(dir: &Path, rel: &str, bytes: &[u8]) -> PathBuf {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn names_packages_like_summaries() {
+        let name = |path: &str, hex: &str| package_name(Path::new(path), &address(hex)).to_string();
+        let deps = "build/app/bytecode_modules/dependencies";
+        assert_eq!(name(&format!("{deps}/MoveStdlib/vector.mv"), "0x1"), "std");
+        assert_eq!(name(&format!("{deps}/Sui/coin.mv"), "0x2"), "sui");
+        assert_eq!(
+            name(&format!("{deps}/DeepBookV3/pool.mv"), "0x0"),
+            "deep_book_v3"
+        );
+        assert_eq!(name("build/MyApp/bytecode_modules/app.mv", "0x0"), "my_app");
+        assert_eq!(name("my-app/app.mv", "0x0"), "my_app");
+    }
+
+    #[test]
+    fn lists_root_modules_before_dependencies() {
+        let dir = TempDir::new("bytecode-order");
+        let modules = dir.path().join("bytecode_modules");
+        let dependency = write(&modules, "dependencies/Alpha/a.mv", b"");
+        let root = write(&modules, "z.mv", b"");
+        write(&modules, "notes.txt", b"");
+        assert_eq!(
+            collect_module_files(&modules).unwrap(),
+            vec![root, dependency]
+        );
+    }
+
+    #[test]
+    fn skips_invalid_modules_only_when_lenient() {
+        let dir = TempDir::new("bytecode-invalid");
+        let path = write(dir.path(), "app/broken.mv", b"not bytecode");
+
+        let err = parse_bytecode(dir.path(), ParseMode::Strict).unwrap_err();
+        assert!(matches!(&err, SummaryError::InvalidBytecode { path: p, .. } if *p == path));
+
+        let parsed = parse_bytecode(dir.path(), ParseMode::Lenient).unwrap();
+        assert!(parsed.packages.packages.is_empty());
+        assert!(matches!(
+            parsed.diagnostics.as_slice(),
+            [Diagnostic::SkippedModule(SummaryError::InvalidBytecode { path: p, .. })] if *p == path
+        ));
+    }
+
+    #[test]
+    fn summarizes_a_build_directory() {
+        let dir = TempDir::new("bytecode-build");
+        let modules = dir
+            .path()
+            .join("build")
+            .join("MyApp")
+            .join("bytecode_modules");
+        write(&modules, "app.mv", &compiled_module(0x0, "app"));
+        write(
+            &modules,
+            "dependencies/Sui/coin.mv",
+            &compiled_module(0x2, "coin"),
+        );
+        let other = write(
+            &modules,
+            "dependencies/Other/other.mv",
+            &compiled_module(0x0, "other"),
+        );
+
+        let parsed = parse_bytecode(&modules, ParseMode::Strict).unwrap();
+        let packages: Vec<_> = parsed
+            .packages
+            .packages
+            .iter()
+            .map(|(address, package)| {
+                let modules: Vec<_> = package.modules.keys().map(|m| m.as_str()).collect();
+                let name = package.name.unwrap();
+                (*address, format!("{name}: {}", modules.join(" ")))
+            })
+            .collect();
+        assert_eq!(
+            packages,
+            vec![
+                (AccountAddress::ZERO, "my_app: app".to_string()),
+                (address("0x2"), "sui: coin".to_string()),
+            ]
+        );
+        assert!(matches!(
+            parsed.diagnostics.as_slice(),
+            [Diagnostic::SharedAddress { kept, ignored, path, .. }]
+                if kept == "my_app" && ignored == "other" && *path == other
+        ));
+        assert_eq!(parsed.file_hashes.len(), 3);
+    }
+}
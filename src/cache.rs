@@ -0,0 +1,332 @@
+use crate::{
+    DocIndex, ModuleGraph, PackageIndex, SearchIndex, TypeUsageIndex, parser::RootPackageMetadata,
+};
+use move_model_2::summary::Packages;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+/// Bump when the layout or the content of the cache or of one of the
+/// `Indexes` changes.
+const CACHE_VERSION: u32 = 5;
+const CACHE_SUFFIX: &str = "summary-cache";
+
+/// Size and modification time of one input file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FileStamp {
+    path: PathBuf,
+    len: u64,
+    modified_nanos: u128,
+}
+
+/// Identifies the state of a summaries folder or archive. The stamps are
+/// cheap to compare, the content hash catches files that were touched but
+/// not changed.
+pub(crate) struct CacheKey {
+    stamps: Vec<FileStamp>,
+    hash: u64,
+}
+
+/// Everything `PackageTree` derives from the packages, so that a fresh
+/// cache skips building them too.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Indexes {
+    pub index: PackageIndex,
+    pub graph: ModuleGraph,
+    pub usages: TypeUsageIndex,
+    pub docs: DocIndex,
+    pub search: SearchIndex,
+}
+
+impl Indexes {
+    pub fn new(packages: &Packages) -> Self {
+        Self {
+            index: PackageIndex::new(packages),
+            graph: ModuleGraph::new(packages),
+            usages: TypeUsageIndex::new(packages),
+            docs: DocIndex::new(packages),
+            search: SearchIndex::new(packages),
+        }
+    }
+}
+
+pub(crate) struct Cached {
+    pub packages: Packages,
+    pub metadata: Option<RootPackageMetadata>,
+    pub indexes: Indexes,
+}
+
+#[derive(Serialize)]
+struct CacheOut<'a> {
+    version: u32,
+    stamps: &'a [FileStamp],
+    hash: u64,
+    packages: &'a Packages,
+    metadata: Option<&'a RootPackageMetadata>,
+    indexes: &'a Indexes,
+}
+
+#[derive(Deserialize)]
+struct CacheIn {
+    version: u32,
+    stamps: Vec<FileStamp>,
+    hash: u64,
+    packages: Packages,
+    metadata: Option<RootPackageMetadata>,
+    indexes: Indexes,
+}
+
+/// The cache lives next to the summaries, e.g. `package_summaries.summary-cache`.
+pub(crate) fn cache_path(summaries: &Path) -> Option<PathBuf> {
+    let summaries = summaries.canonicalize().ok()?;
+    let name = summaries.file_name()?.to_str()?;
+    Some(summaries.with_file_name(format!("{}.{}", name, CACHE_SUFFIX)))
+}
+
+impl CacheKey {
+    /// Key of `summaries` from stamps taken before parsing and the hashes of
+    /// the files the parser read, see `ParsedSummaries::file_hashes`. Only
+    /// files the parser skipped are read again.
+    pub fn new(
+        summaries: &Path,
+        stamps: Vec<FileStamp>,
+        file_hashes: &BTreeMap<PathBuf, u64>,
+    ) -> io::Result<Self> {
+        let hash = content_hash(summaries, &stamps, file_hashes)?;
+        Ok(Self { stamps, hash })
+    }
+}
+
+/// Load the cache for `summaries` if it is still fresh.
+pub(crate) fn load(summaries: &Path) -> Option<Cached> {
+    let path = cache_path(summaries)?;
+    let bytes = fs::read(&path).ok()?;
+    // Human readable, like the summaries, so that datatypes serialized
+    // through `#[serde(flatten)]` read back the same way
+    let mut deserializer = rmp_serde::Deserializer::from_read_ref(&bytes).with_human_readable();
+    let cache = CacheIn::deserialize(&mut deserializer).ok()?;
+    if cache.version != CACHE_VERSION {
+        return None;
+    }
+
+    let stamps = collect_stamps(summaries).ok()?;
+    let cached = Cached {
+        packages: cache.packages,
+        metadata: cache.metadata,
+        indexes: cache.indexes,
+    };
+    if stamps == cache.stamps {
+        return Some(cached);
+    }
+
+    let hash = content_hash(summaries, &stamps, &BTreeMap::new()).ok()?;
+    if hash != cache.hash {
+        return None;
+    }
+
+    // Files were touched but their content is unchanged, refresh the stamps.
+    let key = CacheKey { stamps, hash };
+    if let Err(err) = store(summaries, &key, &cached) {
+        eprintln!("Failed to update summary cache: {}", err);
+    }
+    Some(cached)
+}
+
+/// Write the cache for `summaries`. `key` should be computed before parsing,
+/// so that changes made while parsing invalidate the cache.
+pub(crate) fn store(summaries: &Path, key: &CacheKey, cached: &Cached) -> io::Result<()> {
+    let path = cache_path(summaries)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no cache path"))?;
+    let out = CacheOut {
+        version: CACHE_VERSION,
+        stamps: &key.stamps,
+        hash: key.hash,
+        packages: &cached.packages,
+        metadata: cached.metadata.as_ref(),
+        indexes: &cached.indexes,
+    };
+    let mut bytes = Vec::new();
+    let mut serializer = rmp_serde::Serializer::new(&mut bytes)
+        .with_struct_map()
+        .with_human_readable();
+    out.serialize(&mut serializer).map_err(io::Error::other)?;
+
+    // Write to a temporary file first so readers never see a partial cache.
+    let tmp = path.with_extension(format!("{}.tmp", CACHE_SUFFIX));
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, &path)
+}
+
+/// Stamps of every file below `root` (or of `root` itself for an archive), in path order.
+pub(crate) fn collect_stamps(root: &Path) -> io::Result<Vec<FileStamp>> {
+    let mut stamps = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        let metadata = fs::metadata(&path)?;
+        if metadata.is_dir() {
+            for entry in fs::read_dir(&path)? {
+                pending.push(entry?.path());
+            }
+            continue;
+        }
+        let modified_nanos = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos());
+        stamps.push(FileStamp {
+            path: path.strip_prefix(root).unwrap_or(&path).to_path_buf(),
+            len: metadata.len(),
+            modified_nanos,
+        });
+    }
+    stamps.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(stamps)
+}
+
+/// Hash over every file path and the hash of its contents, taken from
+/// `file_hashes` for files that were already read.
+fn content_hash(
+    root: &Path,
+    stamps: &[FileStamp],
+    file_hashes: &BTreeMap<PathBuf, u64>,
+) -> io::Result<u64> {
+    let mut hash = Fnv::default();
+    for stamp in stamps {
+        hash.feed(stamp.path.to_string_lossy().as_bytes());
+        let file_hash = match file_hashes.get(&stamp.path) {
+            Some(file_hash) => *file_hash,
+            // An archive is stamped as a single file with an empty relative path
+            None if stamp.path.as_os_str().is_empty() => hash_bytes(&fs::read(root)?),
+            None => hash_bytes(&fs::read(root.join(&stamp.path))?),
+        };
+        hash.feed(&file_hash.to_le_bytes());
+    }
+    Ok(hash.0)
+}
+
+/// Content hash of one input file, as recorded in `ParsedSummaries::file_hashes`.
+pub(crate) fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hash = Fnv::default();
+    hash.feed(bytes);
+    hash.0
+}
+
+/// 64-bit FNV-1a
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv {
+    fn feed(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TempDir, pools};
+    use std::time::{Duration, SystemTime};
+
+    /// A folder of summary-like files inside `dir`, so that the cache file
+    /// next to it goes away with `dir`.
+    fn folder(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("package_summaries");
+        fs::create_dir_all(path.join("pool")).unwrap();
+        fs::write(path.join("address_mapping.json"), "{}").unwrap();
+        fs::write(path.join("pool").join("pool.json"), "{}").unwrap();
+        path
+    }
+
+    fn cached() -> Cached {
+        let packages = pools();
+        Cached {
+            indexes: Indexes::new(&packages),
+            packages,
+            metadata: None,
+        }
+    }
+
+    fn store_fresh(folder: &Path) {
+        let stamps = collect_stamps(folder).unwrap();
+        let key = CacheKey::new(folder, stamps, &BTreeMap::new()).unwrap();
+        store(folder, &key, &cached()).unwrap();
+    }
+
+    #[test]
+    fn round_trips_packages_and_indexes() {
+        let dir = TempDir::new("cache-round-trip");
+        let folder = folder(&dir);
+        store_fresh(&folder);
+
+        let loaded = load(&folder).expect("fresh cache");
+        assert_eq!(loaded.packages.packages.len(), 1);
+        let expected = cached().indexes;
+        assert_eq!(
+            loaded.indexes.docs.search("pool", |_| true, 10).len(),
+            expected.docs.search("pool", |_| true, 10).len()
+        );
+        assert_eq!(
+            loaded.indexes.search.search("pool", |_| true, 10).len(),
+            expected.search.search("pool", |_| true, 10).len()
+        );
+        assert_eq!(
+            loaded.indexes.graph.modules().count(),
+            expected.graph.modules().count()
+        );
+    }
+
+    #[test]
+    fn stays_fresh_when_files_are_only_touched() {
+        let dir = TempDir::new("cache-touched");
+        let folder = folder(&dir);
+        store_fresh(&folder);
+
+        let file = fs::File::options()
+            .write(true)
+            .open(folder.join("pool").join("pool.json"))
+            .unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert!(load(&folder).is_some());
+    }
+
+    #[test]
+    fn goes_stale_when_content_changes() {
+        let dir = TempDir::new("cache-changed");
+        let folder = folder(&dir);
+        store_fresh(&folder);
+
+        fs::write(folder.join("pool").join("pool.json"), "[]").unwrap();
+        assert!(load(&folder).is_none());
+    }
+
+    #[test]
+    fn reuses_hashes_of_files_already_read() {
+        let dir = TempDir::new("cache-hashes");
+        let folder = folder(&dir);
+        let stamps = collect_stamps(&folder).unwrap();
+        let read: BTreeMap<PathBuf, u64> = stamps
+            .iter()
+            .map(|stamp| {
+                let content = fs::read(folder.join(&stamp.path)).unwrap();
+                (stamp.path.clone(), hash_bytes(&content))
+            })
+            .collect();
+        assert_eq!(
+            content_hash(&folder, &stamps, &read).unwrap(),
+            content_hash(&folder, &stamps, &BTreeMap::new()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::{Fields, Packages};
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What a documentation entry describes, inside its module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocTarget {
+    Module,
+    Function {
+        name: Symbol,
+    },
+    Struct {
+        name: Symbol,
+    },
+    Enum {
+        name: Symbol,
+    },
+    Variant {
+        datatype: Symbol,
+        variant: Symbol,
+    },
+    Field {
+        datatype: Symbol,
+        field: Symbol,
+    },
+    VariantField {
+        datatype: Symbol,
+        variant: Symbol,
+        field: Symbol,
+    },
+}
+
+impl DocTarget {
+    /// Path of the item inside its module, e.g. `Pool`, `Action::Swap` or
+    /// `Pool.balance`. Empty for the module itself.
+    pub fn item_path(&self) -> String {
+        match self {
+            Self::Module => String::new(),
+            Self::Function { name } | Self::Struct { name } | Self::Enum { name } => {
+                name.to_string()
+            }
+            Self::Variant { datatype, variant } => format!("{}::{}", datatype, variant),
+            Self::Field { datatype, field } => format!("{}.{}", datatype, field),
+            Self::VariantField {
+                datatype,
+                variant,
+                field,
+            } => format!("{}::{}.{}", datatype, variant, field),
+        }
+    }
+}
+
+/// A documented item matching a query.
+#[derive(Debug, Clone, Serialize)]
+pub struct DocHit {
+    /// Address of the package, as a key of `PackageTree::packages`.
+    pub address: AccountAddress,
+    /// Key of the module in `Package::modules`.
+    pub module: Symbol,
+    #[serde(flatten)]
+    pub target: DocTarget,
+    pub score: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DocEntry {
+    address: AccountAddress,
+    module: Symbol,
+    target: DocTarget,
+    /// Number of terms, for length normalization.
+    len: usize,
+}
+
+/// Inverted index over doc comments and item names, ranked with BM25.
+///
+/// Item names are split into words and indexed along with the doc text and
+/// the name of their module, so "split coin" finds `coin::split` even where
+/// the doc comment never says "coin".
+#[derive(Default, Serialize, Deserialize)]
+pub struct DocIndex {
+    entries: Vec<DocEntry>,
+    /// Term to `(entry, term frequency)`, in entry order.
+    postings: HashMap<String, Vec<(usize, u32)>>,
+    average_len: f32,
+}
+
+impl std::fmt::Debug for DocIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DocIndex")
+            .field("entries", &self.entries.len())
+            .field("terms", &self.postings.len())
+            .finish()
+    }
+}
+
+/// BM25 parameters
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+const STOP_WORDS: [&str; 24] = [
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "if", "in", "is", "it", "of",
+    "on", "or", "that", "the", "this", "to", "which", "with", "will",
+];
+
+impl DocIndex {
+    pub fn new(packages: &Packages) -> Self {
+        let mut index = Self::default();
+        for (address, package) in &packages.packages {
+            for (module_key, module) in &package.modules {
+                let mut add = |target: DocTarget, name: &str, doc: Option<&str>| {
+                    index.add(*address, *module_key, target, name, doc);
+                };
+
+                add(
+                    DocTarget::Module,
+                    module.id.name.as_str(),
+                    module.doc.as_deref(),
+                );
+                for (name, function) in &module.functions {
+                    let target = DocTarget::Function { name: *name };
+                    add(target, name.as_str(), function.doc.as_deref());
+                }
+                for (name, struct_) in &module.structs {
+                    let target = DocTarget::Struct { name: *name };
+                    add(target, name.as_str(), struct_.doc.as_deref());
+                    for_each_documented_field(&struct_.fields, |field, doc| {
+                        let target = DocTarget::Field {
+                            datatype: *name,
+                            field,
+                        };
+                        add(target, field.as_str(), Some(doc));
+                    });
+                }
+                for (name, enum_) in &module.enums {
+                    let target = DocTarget::Enum { name: *name };
+                    add(target, name.as_str(), enum_.doc.as_deref());
+                    for (variant_name, variant) in &enum_.variants {
+                        let target = DocTarget::Variant {
+                            datatype: *name,
+                            variant: *variant_name,
+                        };
+                        add(target, variant_name.as_str(), variant.doc.as_deref());
+                        for_each_documented_field(&variant.fields, |field, doc| {
+                            let target = DocTarget::VariantField {
+                                datatype: *name,
+                                variant: *variant_name,
+                                field,
+                            };
+                            add(target, field.as_str(), Some(doc));
+                        });
+                    }
+                }
+            }
+        }
+
+        let total: usize = index.entries.iter().map(|entry| entry.len).sum();
+        index.average_len = total as f32 / index.entries.len().max(1) as f32;
+        index
+    }
+
+    fn add(
+        &mut self,
+        address: AccountAddress,
+        module: Symbol,
+        target: DocTarget,
+        name: &str,
+        doc: Option<&str>,
+    ) {
+        // Undocumented fields and variants only add noise
+        let is_member = matches!(
+            target,
+            DocTarget::Field { .. } | DocTarget::Variant { .. } | DocTarget::VariantField { .. }
+        );
+        if doc.is_none() && is_member {
+            return;
+        }
+
+        let module_name = match target {
+            DocTarget::Module => None,
+            _ => Some(module.as_str()),
+        };
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        let mut len = 0;
+        let terms = terms(name)
+            .chain(module_name.into_iter().flat_map(terms))
+            .chain(doc.into_iter().flat_map(terms));
+        for term in terms {
+            *frequencies.entry(term).or_default() += 1;
+            len += 1;
+        }
+
+        let entry = self.entries.len();
+        self.entries.push(DocEntry {
+            address,
+            module,
+            target,
+            len,
+        });
+        for (term, frequency) in frequencies {
+            self.postings
+                .entry(term)
+                .or_default()
+                .push((entry, frequency));
+        }
+    }
+
+    /// Documented items accepted by `filter`, ranked by how well they match
+    /// `query`, best first.
+    pub fn search(
+        &self,
+        query: &str,
+        filter: impl Fn(&DocHit) -> bool,
+        limit: usize,
+    ) -> Vec<DocHit> {
+        let mut query_terms: Vec<String> = terms(query).collect();
+        query_terms.sort();
+        query_terms.dedup();
+
+        let count = self.entries.len() as f32;
+        let mut scores: HashMap<usize, f32> = HashMap::new();
+        for term in &query_terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f32;
+            let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for (entry, frequency) in postings {
+                let tf = *frequency as f32;
+                let len = self.entries[*entry].len as f32;
+                let norm = K1 * (1.0 - B + B * len / self.average_len.max(1.0));
+                *scores.entry(*entry).or_default() += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut ranked: Vec<(usize, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|(a, a_score), (b, b_score)| b_score.total_cmp(a_score).then(a.cmp(b)));
+        ranked
+            .into_iter()
+            .map(|(entry, score)| {
+                let entry = &self.entries[entry];
+                DocHit {
+                    address: entry.address,
+                    module: entry.module,
+                    target: entry.target.clone(),
+                    score,
+                }
+            })
+            .filter(filter)
+            .take(limit)
+            .collect()
+    }
+}
+
+fn for_each_documented_field(fields: &Fields, mut f: impl FnMut(Symbol, &str)) {
+    for (name, field) in &fields.fields {
+        if let Some(doc) = &field.doc {
+            f(*name, doc);
+        }
+    }
+}
+
+/// Lowercased words of `text` with stop words dropped and plural and verb
+/// endings stripped. Identifiers are split at `_` and camelCase boundaries.
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .flat_map(split_camel_case)
+        .map(|word| stem(&word.to_lowercase()))
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+}
+
+fn split_camel_case(word: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    for window in chars.windows(2) {
+        let [(_, prev), (i, c)] = window else {
+            continue;
+        };
+        if prev.is_lowercase() && c.is_uppercase() {
+            parts.push(&word[start..*i]);
+            start = *i;
+        }
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+/// A light suffix stripper, enough for "splits", "splitting" and "split" to
+/// meet.
+fn stem(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ing")
+        && stem.len() >= 4
+    {
+        // "splitting" -> "splitt" -> "split", but "calling" -> "call"
+        let mut chars = stem.chars().rev();
+        let last = chars.next();
+        if last == chars.next()
+            && last.is_some_and(|c| c.is_ascii_alphabetic() && !"aeiouylsz".contains(c))
+        {
+            return stem[..stem.len() - 1].to_string();
+        }
+        return stem.to_string();
+    }
+    if let Some(stem) = word.strip_suffix('s')
+        && stem.len() >= 3
+        && !stem.ends_with('s')
+    {
+        return stem.to_string();
+    }
+    word.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pools;
+
+    fn index() -> DocIndex {
+        DocIndex::new(&pools())
+    }
+
+    #[test]
+    fn stems_plurals_and_verb_endings() {
+        assert_eq!(stem("splits"), "split");
+        assert_eq!(stem("splitting"), "split");
+        assert_eq!(stem("split"), "split");
+        assert_eq!(stem("calling"), "call");
+        assert_eq!(stem("passing"), "pass");
+        assert_eq!(stem("buzzing"), "buzz");
+        assert_eq!(stem("address"), "address");
+        assert_eq!(stem("is"), "is");
+    }
+
+    #[test]
+    fn splits_identifiers_into_terms() {
+        let terms: Vec<String> = terms("split_coin intoBalance of the Pools").collect();
+        assert_eq!(terms, ["split", "coin", "into", "balance", "pool"]);
+    }
+
+    #[test]
+    fn ranks_items_by_name_and_module() {
+        let hits = index().search("type pool", |_| true, 10);
+        assert_eq!(
+            hits[0].target,
+            DocTarget::Function {
+                name: Symbol::from("type")
+            }
+        );
+        assert!(hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        let hits = index().search("balances", |_| true, 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].target, DocTarget::Module);
+    }
+
+    #[test]
+    fn filters_before_the_limit() {
+        let index = index();
+        let hits = index.search("pool", |hit| !matches!(hit.target, DocTarget::Module), 1);
+        assert_eq!(hits.len(), 1);
+        assert_ne!(hits[0].target, DocTarget::Module);
+        assert!(index.search("pool", |_| false, 10).is_empty());
+    }
+}
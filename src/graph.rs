@@ -0,0 +1,241 @@
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::Packages;
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A module by its own address and name, as in `summary::ModuleId`.
+pub type ModuleKey = (AccountAddress, Symbol);
+
+/// Module level dependency graph built from `immediate_dependencies`.
+///
+/// Dependencies that are not loaded are still nodes, without edges of their
+/// own.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ModuleGraph {
+    dependencies: BTreeMap<ModuleKey, BTreeSet<ModuleKey>>,
+    dependents: BTreeMap<ModuleKey, BTreeSet<ModuleKey>>,
+    /// See `cycles`.
+    cycles: Vec<Vec<ModuleKey>>,
+}
+
+impl ModuleGraph {
+    pub fn new(packages: &Packages) -> Self {
+        let mut graph = Self::default();
+        for module in packages
+            .packages
+            .values()
+            .flat_map(|package| package.modules.values())
+        {
+            let key = (module.id.address, module.id.name);
+            graph.dependents.entry(key).or_default();
+            let dependencies = graph.dependencies.entry(key).or_default();
+            for dependency in &module.immediate_dependencies {
+                let dependency = (dependency.address, dependency.name);
+                dependencies.insert(dependency);
+                graph.dependents.entry(dependency).or_default().insert(key);
+            }
+        }
+        for key in graph.dependents.keys() {
+            graph.dependencies.entry(*key).or_default();
+        }
+        graph.cycles = graph
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || graph.dependencies[&component[0]].contains(&component[0])
+            })
+            .collect();
+        graph
+    }
+
+    /// Every module in the graph, loaded or only depended upon.
+    pub fn modules(&self) -> impl Iterator<Item = &ModuleKey> {
+        self.dependencies.keys()
+    }
+
+    /// Modules `module` depends on directly.
+    pub fn dependencies(&self, module: &ModuleKey) -> Option<&BTreeSet<ModuleKey>> {
+        self.dependencies.get(module)
+    }
+
+    /// Modules that depend on `module` directly.
+    pub fn dependents(&self, module: &ModuleKey) -> Option<&BTreeSet<ModuleKey>> {
+        self.dependents.get(module)
+    }
+
+    /// Modules `module` depends on, directly or not.
+    pub fn transitive_dependencies(&self, module: &ModuleKey) -> BTreeSet<ModuleKey> {
+        reachable(&self.dependencies, module)
+    }
+
+    /// Modules that depend on `module`, directly or not.
+    pub fn transitive_dependents(&self, module: &ModuleKey) -> BTreeSet<ModuleKey> {
+        reachable(&self.dependents, module)
+    }
+
+    /// Modules ordered so that every module comes after its dependencies.
+    /// Modules on a cycle are placed after everything they depend on outside
+    /// the cycle, in address and name order; see `cycles`.
+    pub fn topological_order(&self) -> Vec<ModuleKey> {
+        let mut order = Vec::with_capacity(self.dependencies.len());
+        for component in self.strongly_connected_components() {
+            order.extend(component);
+        }
+        order
+    }
+
+    /// Groups of modules that depend on each other, across packages.
+    pub fn cycles(&self) -> &[Vec<ModuleKey>] {
+        &self.cycles
+    }
+
+    /// Tarjan's algorithm, iterative. Components come out dependencies first.
+    fn strongly_connected_components(&self) -> Vec<Vec<ModuleKey>> {
+        struct Visit {
+            index: usize,
+            lowlink: usize,
+            on_stack: bool,
+        }
+
+        let mut visits: BTreeMap<ModuleKey, Visit> = BTreeMap::new();
+        let mut stack: Vec<ModuleKey> = Vec::new();
+        let mut components = Vec::new();
+
+        for root in self.dependencies.keys() {
+            if visits.contains_key(root) {
+                continue;
+            }
+            // (node, iterator position over its dependencies)
+            let mut work: Vec<(ModuleKey, usize)> = vec![(*root, 0)];
+            while let Some((node, position)) = work.pop() {
+                if position == 0 {
+                    let index = visits.len();
+                    visits.insert(
+                        node,
+                        Visit {
+                            index,
+                            lowlink: index,
+                            on_stack: true,
+                        },
+                    );
+                    stack.push(node);
+                }
+
+                let next = self.dependencies[&node].iter().nth(position);
+                if let Some(dependency) = next {
+                    work.push((node, position + 1));
+                    match visits.get(dependency) {
+                        None => work.push((*dependency, 0)),
+                        Some(visit) if visit.on_stack => {
+                            let index = visit.index;
+                            let node_visit = visits.get_mut(&node).expect("visited");
+                            node_visit.lowlink = node_visit.lowlink.min(index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                let Visit { index, lowlink, .. } = visits[&node];
+                if lowlink == index {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        visits.get_mut(&member).expect("visited").on_stack = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+                if let Some((parent, _)) = work.last() {
+                    let parent_visit = visits.get_mut(parent).expect("visited");
+                    parent_visit.lowlink = parent_visit.lowlink.min(lowlink);
+                }
+            }
+        }
+
+        components
+    }
+}
+
+fn reachable(
+    edges: &BTreeMap<ModuleKey, BTreeSet<ModuleKey>>,
+    start: &ModuleKey,
+) -> BTreeSet<ModuleKey> {
+    let mut seen = BTreeSet::new();
+    let mut pending: Vec<ModuleKey> = edges.get(start).into_iter().flatten().copied().collect();
+    while let Some(module) = pending.pop() {
+        if seen.insert(module) {
+            pending.extend(edges.get(&module).into_iter().flatten().copied());
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{packages, pool_module};
+    use move_model_2::summary::{Module, ModuleId};
+
+    /// Modules of one package at `0x2`, each with its dependencies.
+    fn graph(modules: &[(&str, &[&str])]) -> ModuleGraph {
+        let template = pool_module();
+        let id = |name: &str| ModuleId {
+            address: template.id.address,
+            name: Symbol::from(name),
+        };
+        ModuleGraph::new(&packages(modules.iter().map(|(name, dependencies)| {
+            Module {
+                id: id(name),
+                immediate_dependencies: dependencies.iter().map(|name| id(name)).collect(),
+                ..template.clone()
+            }
+        })))
+    }
+
+    fn names(modules: &[ModuleKey]) -> Vec<&str> {
+        modules.iter().map(|(_, name)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn orders_dependencies_first() {
+        let graph = graph(&[("c", &["b"]), ("b", &["a"]), ("a", &["balance"])]);
+        assert_eq!(
+            names(&graph.topological_order()),
+            ["balance", "a", "b", "c"]
+        );
+        assert!(graph.cycles().is_empty());
+    }
+
+    #[test]
+    fn finds_cycles_and_self_loops() {
+        let graph = graph(&[
+            ("a", &["b"]),
+            ("b", &["c"]),
+            ("c", &["a", "d"]),
+            ("d", &["d"]),
+            ("e", &["a"]),
+        ]);
+        let cycles: Vec<_> = graph.cycles().iter().map(|cycle| names(cycle)).collect();
+        assert_eq!(cycles, [vec!["d"], vec!["a", "b", "c"]]);
+        assert_eq!(names(&graph.topological_order()), ["d", "a", "b", "c", "e"]);
+    }
+
+    #[test]
+    fn follows_edges_transitively() {
+        let graph = graph(&[("a", &["b"]), ("b", &["c"]), ("d", &["b"])]);
+        let key = |name: &str| (graph.modules().next().unwrap().0, Symbol::from(name));
+        assert_eq!(
+            names(&Vec::from_iter(graph.transitive_dependencies(&key("a")))),
+            ["b", "c"]
+        );
+        assert_eq!(
+            names(&Vec::from_iter(graph.transitive_dependents(&key("c")))),
+            ["a", "b", "d"]
+        );
+    }
+}
@@ -1,27 +1,40 @@
 mod update;
 mod view;
 
+use iced::futures::{SinkExt, Stream};
 use iced::{Color, Subscription, Task};
 use move_core_types::account_address::AccountAddress;
-use move_model_2::summary::{Package, Type};
 use move_symbol_pool::symbol::Symbol;
-use std::collections::BTreeMap;
 use std::fmt::Debug;
 use update::update;
 use view::view;
 
+pub use crate::render::type_to_string;
+
 #[derive(Debug, Clone)]
 enum Message {
     SelectPackage(AccountAddress),
     SelectModule(Symbol),
     SelectDefinition(DefType, Symbol),
+    OpenModule(AccountAddress, Symbol),
+    OpenDefinition(AccountAddress, Symbol, DefType, Symbol),
+    CopyInterface(AccountAddress, Symbol),
     SetView(View),
     SearchInputChanged(String),
     ToggleStdFilter(bool),
     ToggleSuiFilter(bool),
     TogglePublicOnly(bool),
+    ToggleSignatureExact(bool),
+    ToggleDocsSearch(bool),
     SelectFromSearch(AccountAddress, Symbol, Option<(DefType, Symbol)>),
     PickFolder,
+    PickArchive,
+    ReloadPackages,
+    /// A folder or archive picked by the user finished loading.
+    PackagesLoaded(std::path::PathBuf, Result<crate::PackageTree, String>),
+    /// A reload of the watched folder, started in the given
+    /// `State::reload_generation`, finished.
+    PackagesReloaded(u64, Result<crate::PackageTree, String>),
     ClearPackages,
 }
 
@@ -36,6 +49,7 @@ enum DefType {
 enum View {
     Explorer,
     Search,
+    Diagnostics,
 }
 
 enum Selection {
@@ -45,53 +59,82 @@ enum Selection {
     DefinitionSelected(AccountAddress, Symbol, DefType, Symbol),
 }
 
+/// A ranked match of the Search view, by name, signature or doc comment,
+/// computed when the query, the filters or the packages change.
 #[derive(Debug, Clone)]
-enum SearchItem {
-    Module {
-        package_addr: AccountAddress,
-        module_name: Symbol,
-        display: String,
-    },
-    Definition {
-        package_addr: AccountAddress,
-        module_name: Symbol,
-        def_type: DefType,
-        def_name: Symbol,
-        display: String,
-    },
+struct SearchResult {
+    package_addr: AccountAddress,
+    module_name: Symbol,
+    definition: Option<(DefType, Symbol)>,
+    /// Shown before `path`, e.g. `"  Function: "`.
+    label: &'static str,
+    path: String,
+    /// Character indices of `path` to highlight.
+    positions: Vec<usize>,
 }
 
 struct State {
     selection: Selection,
-    packages: Option<BTreeMap<AccountAddress, Package>>,
+    packages: Option<crate::PackageTree>,
+    /// Folder or archive `packages` was loaded from, watched for changes.
+    source_path: Option<std::path::PathBuf>,
+    load_error: Option<String>,
+    load_options: crate::LoadOptions,
+    /// Bumped by every reload and whenever `packages` is replaced, so that
+    /// only the latest reload of the current folder is applied.
+    reload_generation: u64,
     view: View,
     search_input: String,
     std_filter: bool,
     sui_filter: bool,
     public_only: bool,
+    /// Match signature queries in the Search view exactly instead of by
+    /// containment.
+    signature_exact: bool,
+    /// Search doc comments instead of names in the Search view.
+    docs_search: bool,
+    search_results: Vec<SearchResult>,
+    /// Why the current query could not be run, e.g. an invalid signature.
+    search_error: Option<String>,
 }
 
-pub async fn main<P: Into<std::path::PathBuf>>(folder: Option<P>) -> anyhow::Result<()> {
+pub async fn main<P: Into<std::path::PathBuf>>(
+    folder: Option<P>,
+    load_options: crate::LoadOptions,
+) -> anyhow::Result<()> {
     let folder_path = folder.map(|x| -> std::path::PathBuf { x.into() });
 
     iced::application(
         move || {
-            let summary = folder_path.clone().and_then(|path| {
-                let res = crate::parser::parse_summaries(&path).ok();
-                if res.is_none() {
-                    eprintln!("Invalid path!");
+            let (summary, load_error) = match folder_path
+                .as_deref()
+                .map(|path| crate::PackageTree::load(path, load_options))
+            {
+                Some(Ok(tree)) => (Some(tree), None),
+                Some(Err(err)) => {
+                    eprintln!("Invalid path: {}", err);
+                    (None, Some(err.to_string()))
                 }
-                res
-            });
+                None => (None, None),
+            };
+            let source_path = summary.as_ref().and(folder_path.clone());
             (
                 State {
                     selection: Selection::NoSelection,
-                    packages: summary.map(|s| s.packages),
+                    packages: summary,
+                    source_path,
+                    load_error,
+                    load_options,
+                    reload_generation: 0,
                     view: View::Explorer,
                     search_input: String::new(),
                     std_filter: false,
                     sui_filter: false,
                     public_only: false,
+                    signature_exact: false,
+                    docs_search: false,
+                    search_results: Vec::new(),
+                    search_error: None,
                 },
                 Task::none(),
             )
@@ -110,54 +153,30 @@ pub async fn main<P: Into<std::path::PathBuf>>(folder: Option<P>) -> anyhow::Res
     Ok(())
 }
 
-fn subscription(_state: &State) -> Subscription<Message> {
-    Subscription::none()
+fn subscription(state: &State) -> Subscription<Message> {
+    match &state.source_path {
+        Some(path) => Subscription::run_with(path.clone(), watch_folder),
+        None => Subscription::none(),
+    }
 }
 
-pub fn type_to_string(t: &Type) -> String {
-    match t {
-        Type::Bool => "bool".to_string(),
-        Type::U8 => "u8".to_string(),
-        Type::U16 => "u16".to_string(),
-        Type::U32 => "u32".to_string(),
-        Type::U64 => "u64".to_string(),
-        Type::U128 => "u128".to_string(),
-        Type::U256 => "u256".to_string(),
-        Type::Address => "address".to_string(),
-        Type::Signer => "signer".to_string(),
-        Type::Datatype(dt) => {
-            let args: Vec<String> = dt
-                .type_arguments
-                .iter()
-                .map(|arg| {
-                    //let phantom = if arg.phantom { "phantom " } else { "" };
-                    //format!("{}{}", phantom, type_to_string(&arg.argument))
-
-                    type_to_string(&arg.argument)
-                })
-                .collect();
-            let args_str = if args.is_empty() {
-                "".to_string()
-            } else {
-                format!("<{}>", args.join(", "))
-            };
-            format!("{}{}", dt.name, args_str)
+// `Subscription::run_with` hands the data over as `&PathBuf`.
+#[allow(clippy::ptr_arg)]
+fn watch_folder(path: &std::path::PathBuf) -> impl Stream<Item = Message> + use<> {
+    let path = path.clone();
+    iced::stream::channel(10, async move |mut output| {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let _watcher = match crate::reload::watch_paths(std::slice::from_ref(&path), tx) {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                eprintln!("Failed to watch {}: {}", path.display(), err);
+                return;
+            }
+        };
+        while rx.recv().await.is_some() {
+            if output.send(Message::ReloadPackages).await.is_err() {
+                break;
+            }
         }
-        Type::Vector(inner) => format!("vector<{}>", type_to_string(inner)),
-        Type::Reference(is_mut, inner) => {
-            let mut_str = if *is_mut { "mut " } else { "" };
-            format!("&{}{}", mut_str, type_to_string(inner))
-        }
-        Type::TypeParameter(idx) => format!("T{}", idx),
-        Type::NamedTypeParameter(sym) => sym.to_string(),
-        Type::Tuple(types) => {
-            let type_strs: Vec<String> = types.iter().map(|t| type_to_string(t)).collect();
-            format!("({})", type_strs.join(", "))
-        }
-        Type::Fun(args, ret) => {
-            let arg_strs: Vec<String> = args.iter().map(|t| type_to_string(t)).collect();
-            format!("fun({}) -> {}", arg_strs.join(", "), type_to_string(ret))
-        }
-        Type::Any => "_".to_string(),
-    }
+    })
 }
@@ -1,6 +1,13 @@
-use super::{Message, Selection, State, View};
+use super::{DefType, Message, SearchResult, Selection, State, View};
+use iced::Task;
+use move_core_types::account_address::AccountAddress;
 
-pub fn update(state: &mut State, message: Message) {
+/// Maximum number of results shown by the Search view
+const SEARCH_RESULT_LIMIT: usize = 200;
+/// Maximum number of results shown for a doc comment search
+const DOCS_RESULT_LIMIT: usize = 100;
+
+pub fn update(state: &mut State, message: Message) -> Task<Message> {
     match message {
         Message::SelectPackage(package_addr) => {
             state.selection = Selection::PackageSelected(package_addr);
@@ -36,20 +43,48 @@ pub fn update(state: &mut State, message: Message) {
             }
             _ => {}
         },
+        Message::OpenModule(package_addr, module_name) => {
+            state.selection = Selection::ModuleSelected(package_addr, module_name);
+        }
+        Message::OpenDefinition(package_addr, module_name, def_type, def_name) => {
+            state.selection =
+                Selection::DefinitionSelected(package_addr, module_name, def_type, def_name);
+        }
+        Message::CopyInterface(package_addr, module_name) => {
+            let interface = state
+                .packages
+                .as_ref()
+                .and_then(|packages| packages.module_interface(&(package_addr, module_name)));
+            if let Some(interface) = interface {
+                return iced::clipboard::write(interface);
+            }
+        }
         Message::SetView(view) => {
             state.view = view;
         }
         Message::SearchInputChanged(input) => {
             state.search_input = input;
+            refresh_search(state);
         }
         Message::ToggleStdFilter(enabled) => {
             state.std_filter = enabled;
+            refresh_search(state);
         }
         Message::ToggleSuiFilter(enabled) => {
             state.sui_filter = enabled;
+            refresh_search(state);
         }
         Message::TogglePublicOnly(enabled) => {
             state.public_only = enabled;
+            refresh_search(state);
+        }
+        Message::ToggleSignatureExact(enabled) => {
+            state.signature_exact = enabled;
+            refresh_search(state);
+        }
+        Message::ToggleDocsSearch(enabled) => {
+            state.docs_search = enabled;
+            refresh_search(state);
         }
         Message::SelectFromSearch(package_addr, module_name, definition) => {
             state.view = View::Explorer;
@@ -73,20 +108,287 @@ pub fn update(state: &mut State, message: Message) {
                 .set_directory(current_dir)
                 .pick_folder();
             if let Some(path) = folder {
-                match crate::parser::parse_summaries(&path) {
-                    Ok(summary) => {
-                        state.packages = Some(summary.packages);
-                        state.selection = Selection::NoSelection;
-                    }
-                    Err(_) => {
-                        eprintln!("Invalid folder!")
-                    }
+                return load_packages(state, path, Message::PackagesLoaded);
+            }
+        }
+        Message::PickArchive => {
+            let current_dir = std::env::current_dir().expect("current_dir not found");
+            let archive = rfd::FileDialog::new()
+                .set_directory(current_dir)
+                .add_filter("Summary archives", &["zip", "tar", "tar.gz", "tgz"])
+                .pick_file();
+            match archive {
+                // Dialogs match the last extension only, so `.gz` files can
+                // get through
+                Some(path) if !crate::source::is_archive(&path) => {
+                    state.load_error = Some(format!(
+                        "Not a .zip, .tar, .tar.gz or .tgz archive: {}",
+                        path.display()
+                    ));
                 }
+                Some(path) => return load_packages(state, path, Message::PackagesLoaded),
+                None => {}
             }
         }
+        Message::ReloadPackages => {
+            let Some(path) = state.source_path.clone() else {
+                return Task::none();
+            };
+            state.reload_generation += 1;
+            let generation = state.reload_generation;
+            return load_packages(state, path, move |_, result| {
+                Message::PackagesReloaded(generation, result)
+            });
+        }
+        Message::PackagesLoaded(path, result) => match result {
+            Ok(summary) => {
+                state.reload_generation += 1;
+                state.packages = Some(summary);
+                state.source_path = Some(path);
+                state.load_error = None;
+                state.selection = Selection::NoSelection;
+                refresh_search(state);
+            }
+            Err(err) => {
+                eprintln!("Invalid folder: {}", err);
+                state.load_error = Some(err);
+            }
+        },
+        // Drop reloads superseded by a later reload, or of a folder that was
+        // cleared or replaced meanwhile.
+        Message::PackagesReloaded(generation, _) if generation != state.reload_generation => {}
+        Message::PackagesReloaded(_, result) => match result {
+            Ok(summary) => {
+                state.selection = reselect(&state.selection, &summary);
+                state.packages = Some(summary);
+                refresh_search(state);
+            }
+            Err(err) => {
+                eprintln!("Reload failed, keeping previous summaries: {}", err);
+            }
+        },
         Message::ClearPackages => {
+            state.reload_generation += 1;
             state.packages = None;
+            state.source_path = None;
             state.selection = Selection::NoSelection;
+            refresh_search(state);
+        }
+    }
+    Task::none()
+}
+
+/// Load `path` on a thread of its own, as parsing blocks, and deliver the
+/// result as `done`.
+fn load_packages(
+    state: &State,
+    path: std::path::PathBuf,
+    done: impl FnOnce(std::path::PathBuf, Result<crate::PackageTree, String>) -> Message
+    + Send
+    + 'static,
+) -> Task<Message> {
+    let options = state.load_options;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let loading = path.clone();
+    std::thread::spawn(move || {
+        let result = crate::PackageTree::load(&loading, options).map_err(|err| err.to_string());
+        let _ = tx.send(result);
+    });
+    Task::perform(
+        async move {
+            let result = rx
+                .await
+                .unwrap_or_else(|_| Err("Loading stopped unexpectedly".to_string()));
+            (path, result)
+        },
+        move |(path, result)| done(path, result),
+    )
+}
+
+/// Keep `selection` if it still exists in `packages`, otherwise fall back to
+/// the nearest parent that does.
+fn reselect(selection: &Selection, packages: &crate::PackageTree) -> Selection {
+    let (addr, module_name, definition) = match selection {
+        Selection::NoSelection => return Selection::NoSelection,
+        Selection::PackageSelected(addr) => (*addr, None, None),
+        Selection::ModuleSelected(addr, module_name) => (*addr, Some(*module_name), None),
+        Selection::DefinitionSelected(addr, module_name, def_type, def_name) => {
+            (*addr, Some(*module_name), Some((*def_type, *def_name)))
         }
+    };
+
+    let Some(package) = packages.get_package_by_address(&addr) else {
+        return Selection::NoSelection;
+    };
+    let Some((module_name, module)) =
+        module_name.and_then(|name| package.modules.get(&name).map(|module| (name, module)))
+    else {
+        return Selection::PackageSelected(addr);
+    };
+    let Some((def_type, def_name)) = definition else {
+        return Selection::ModuleSelected(addr, module_name);
+    };
+
+    let exists = match def_type {
+        DefType::Function => module.functions.contains_key(&def_name),
+        DefType::Struct => module.structs.contains_key(&def_name),
+        DefType::Enum => module.enums.contains_key(&def_name),
+    };
+    if exists {
+        Selection::DefinitionSelected(addr, module_name, def_type, def_name)
+    } else {
+        Selection::ModuleSelected(addr, module_name)
     }
 }
+
+/// Rerun the search for the current query and filters: by signature for
+/// queries starting like one, e.g. `(&mut TxContext) -> Coin<_>`, by doc
+/// comment when enabled, and by fuzzy name otherwise.
+fn refresh_search(state: &mut State) {
+    state.search_results.clear();
+    state.search_error = None;
+    let Some(packages) = &state.packages else {
+        return;
+    };
+    let query = state.search_input.trim_start();
+    let keep_package = |address: &AccountAddress| {
+        let package_name = packages.index().package_name(address);
+        (state.sui_filter || package_name != Some("sui"))
+            && (state.std_filter || package_name != Some("std"))
+    };
+
+    let results = if query.starts_with('(') || query.starts_with("->") {
+        match crate::SignatureQuery::parse(query) {
+            Ok(query) => signature_results(state, packages, &query, keep_package),
+            Err(err) => {
+                state.search_error = Some(format!("Invalid signature query: {}", err));
+                return;
+            }
+        }
+    } else if state.docs_search {
+        if query.trim().is_empty() {
+            return;
+        }
+        docs_results(packages, query, keep_package)
+    } else {
+        name_results(state, packages, query)
+    };
+    state.search_results = results;
+}
+
+fn name_results(state: &State, packages: &crate::PackageTree, query: &str) -> Vec<SearchResult> {
+    let filter = |entry: &crate::SearchEntry| {
+        (state.sui_filter || entry.package.as_str() != "sui")
+            && (state.std_filter || entry.package.as_str() != "std")
+            && (!state.public_only || entry.is_public)
+    };
+    packages
+        .search_index()
+        .search(query, filter, SEARCH_RESULT_LIMIT)
+        .into_iter()
+        .map(|hit| {
+            let entry = hit.entry;
+            let (definition, label) = match entry.kind {
+                crate::ItemKind::Module => (None, "Module: "),
+                crate::ItemKind::Function => {
+                    (Some((DefType::Function, entry.name)), "  Function: ")
+                }
+                crate::ItemKind::Struct => (Some((DefType::Struct, entry.name)), "  Struct: "),
+                crate::ItemKind::Enum => (Some((DefType::Enum, entry.name)), "  Enum: "),
+            };
+            SearchResult {
+                package_addr: entry.address,
+                module_name: entry.module,
+                definition,
+                label,
+                path: entry.path.clone(),
+                positions: hit.positions,
+            }
+        })
+        .collect()
+}
+
+fn signature_results(
+    state: &State,
+    packages: &crate::PackageTree,
+    query: &crate::SignatureQuery,
+    keep_package: impl Fn(&AccountAddress) -> bool,
+) -> Vec<SearchResult> {
+    let mode = if state.signature_exact {
+        crate::MatchMode::Exact
+    } else {
+        crate::MatchMode::Contains
+    };
+    packages
+        .search_by_signature(query, mode)
+        .into_iter()
+        .filter(|def| keep_package(&def.address))
+        .filter_map(|def| {
+            let function = packages.get_function_by_ref(&def)?;
+            // TODO: Visibility doesn't have PartialEq
+            let is_public = matches!(
+                function.visibility,
+                move_model_2::summary::Visibility::Public
+            );
+            if state.public_only && !is_public {
+                return None;
+            }
+            let path = format!(
+                "{}.{}.{}",
+                packages
+                    .index()
+                    .package_name(&def.address)
+                    .unwrap_or_default(),
+                def.module,
+                crate::render::function_signature(def.name, function)
+            );
+            Some(SearchResult {
+                package_addr: def.address,
+                module_name: def.module,
+                definition: Some((DefType::Function, def.name)),
+                label: "  Function: ",
+                path,
+                positions: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn docs_results(
+    packages: &crate::PackageTree,
+    query: &str,
+    keep_package: impl Fn(&AccountAddress) -> bool,
+) -> Vec<SearchResult> {
+    packages
+        .search_docs(query, |hit| keep_package(&hit.address), DOCS_RESULT_LIMIT)
+        .into_iter()
+        .map(|hit| {
+            let definition = match &hit.target {
+                crate::DocTarget::Module => None,
+                crate::DocTarget::Function { name } => Some((DefType::Function, *name)),
+                crate::DocTarget::Struct { name }
+                | crate::DocTarget::Field { datatype: name, .. } => Some((DefType::Struct, *name)),
+                crate::DocTarget::Enum { name }
+                | crate::DocTarget::Variant { datatype: name, .. }
+                | crate::DocTarget::VariantField { datatype: name, .. } => {
+                    Some((DefType::Enum, *name))
+                }
+            };
+            let module_path = packages.module_path(&(hit.address, hit.module));
+            let item = hit.target.item_path();
+            let (label, path) = if item.is_empty() {
+                ("Module: ", module_path)
+            } else {
+                ("  ", format!("{}::{}", module_path, item))
+            };
+            SearchResult {
+                package_addr: hit.address,
+                module_name: hit.module,
+                definition,
+                label,
+                path,
+                positions: Vec::new(),
+            }
+        })
+        .collect()
+}
@@ -1,26 +1,37 @@
 use iced::{
     Alignment, Background, Color, Element, Length,
-    widget::{button, checkbox, column, container, row, rule, scrollable, space, text, text_input},
+    widget::{
+        button, checkbox, column, container, rich_text, row, rule, scrollable, space, span, text,
+        text::Span, text_input,
+    },
 };
 use move_symbol_pool::symbol::Symbol;
 
-use super::{DefType, Message, SearchItem, Selection, State, View, type_to_string};
+use super::{DefType, Message, Selection, State, View};
 
 pub fn view(state: &State) -> Element<'_, Message> {
     if state.packages.is_none() {
-        return container(
-            column![
-                text("No package_summaries folder selected").size(24),
-                button("Select Folder").on_press(Message::PickFolder)
+        let mut landing = column![
+            text("No package_summaries folder selected").size(24),
+            row![
+                button("Select Folder").on_press(Message::PickFolder),
+                button("Select Archive").on_press(Message::PickArchive),
             ]
-            .spacing(20)
-            .align_x(Alignment::Center),
-        )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .align_x(Alignment::Center)
-        .align_y(Alignment::Center)
-        .into();
+            .spacing(10)
+        ]
+        .spacing(20)
+        .align_x(Alignment::Center);
+
+        if let Some(err) = &state.load_error {
+            landing = landing.push(text(err).size(14).color(Color::from_rgb(0.9, 0.3, 0.3)));
+        }
+
+        return container(landing)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::Center)
+            .align_y(Alignment::Center)
+            .into();
     }
 
     let explorer_button = if state.view == View::Explorer {
@@ -48,13 +59,34 @@ pub fn view(state: &State) -> Element<'_, Message> {
     let public_only_checkbox =
         checkbox("public only", state.public_only).on_toggle(Message::TogglePublicOnly);
 
-    let view_buttons = row![
+    let diagnostics_count = state
+        .packages
+        .as_ref()
+        .map_or(0, |packages| packages.diagnostics().len());
+
+    let mut view_buttons = row![
         row![explorer_button, search_button, public_only_checkbox].spacing(10),
         space::horizontal(),
-        clear_button,
     ]
+    .spacing(10)
     .width(Length::Fill);
 
+    if diagnostics_count > 0 {
+        let label = text(format!("Diagnostics ({})", diagnostics_count));
+        let diagnostics_button = if state.view == View::Diagnostics {
+            button(label)
+                .on_press(Message::SetView(View::Diagnostics))
+                .style(selected_button_style)
+        } else {
+            button(label)
+                .on_press(Message::SetView(View::Diagnostics))
+                .style(default_button_style)
+        };
+        view_buttons = view_buttons.push(diagnostics_button);
+    }
+
+    let view_buttons = view_buttons.push(clear_button);
+
     let main_content: Element<Message> = match state.view {
         View::Explorer => {
             let packages_column = build_packages_column(state);
@@ -72,9 +104,15 @@ pub fn view(state: &State) -> Element<'_, Message> {
             .into()
         }
         View::Search => build_search_view(state),
+        View::Diagnostics => build_diagnostics_view(state),
     };
 
-    column![view_buttons, main_content]
+    let mut page = column![view_buttons].spacing(10);
+    if let Some(header) = state.packages.as_ref().and_then(build_root_header) {
+        page = page.push(header);
+    }
+
+    page.push(main_content)
         .spacing(10)
         .padding(10)
         .width(Length::Fill)
@@ -82,6 +120,57 @@ pub fn view(state: &State) -> Element<'_, Message> {
         .into()
 }
 
+fn build_root_header(packages: &crate::PackageTree) -> Option<Element<'_, Message>> {
+    let root = packages.root_package();
+    let metadata = packages.metadata();
+    if root.is_none() && metadata.is_none() {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    if let Some(addr) = root {
+        let name = packages.index().package_name(&addr).unwrap_or("?");
+        parts.push(format!(
+            "Root package: {} ({})",
+            name,
+            addr.to_hex_literal()
+        ));
+    }
+    if let Some(metadata) = metadata {
+        if let Some(id) = &metadata.root_package_id {
+            parts.push(format!("id: {}", id));
+        }
+        if let Some(id) = &metadata.root_package_original_id {
+            parts.push(format!("original id: {}", id));
+        }
+        if let Some(version) = metadata.root_package_version {
+            parts.push(format!("version: {}", version));
+        }
+    }
+
+    Some(
+        container(text(parts.join("  |  ")).size(14))
+            .padding(8)
+            .width(Length::Fill)
+            .style(|_| {
+                container::background(Background::Color(Color::from_rgb8(17, 24, 39)))
+                    .border(iced::border::rounded(8))
+            })
+            .into(),
+    )
+}
+
+fn build_diagnostics_view(state: &State) -> Element<'_, Message> {
+    let lines: Vec<Element<Message>> = state
+        .packages
+        .iter()
+        .flat_map(|packages| packages.diagnostics())
+        .map(|diagnostic| text(diagnostic.to_string()).size(14).into())
+        .collect();
+
+    build_column("Diagnostics", column(lines).spacing(6).into())
+}
+
 fn build_column<'a>(header: &'a str, elem: Element<'a, Message>) -> Element<'a, Message> {
     container(
         column![
@@ -108,15 +197,20 @@ fn build_packages_column(state: &State) -> Element<'_, Message> {
         _ => None,
     };
 
-    let package_buttons: Vec<_> = state
-        .packages
-        .as_ref()
-        .expect("state.packages == None")
+    let packages = state.packages.as_ref().expect("state.packages == None");
+
+    let package_buttons: Vec<_> = packages
+        .packages()
         .iter()
         .filter_map(|(addr, pkg)| {
             pkg.name.as_ref().map(|name| {
                 let is_selected = Some(*addr) == selected_addr;
-                let mut btn = button(text(name.to_string()))
+                let label = if packages.is_root_package(addr) {
+                    format!("{} (root)", name)
+                } else {
+                    name.to_string()
+                };
+                let mut btn = button(text(label))
                     .on_press(Message::SelectPackage(*addr))
                     .width(Length::Fill);
 
@@ -147,7 +241,11 @@ fn build_modules_column(state: &State) -> Element<'_, Message> {
         Selection::PackageSelected(addr)
         | Selection::ModuleSelected(addr, _)
         | Selection::DefinitionSelected(addr, _, _, _) => {
-            if let Some(package) = state.packages.as_ref().and_then(|p| p.get(addr)) {
+            if let Some(package) = state
+                .packages
+                .as_ref()
+                .and_then(|p| p.get_package_by_address(addr))
+            {
                 let module_buttons: Vec<_> = package
                     .modules
                     .keys()
@@ -192,16 +290,21 @@ fn build_definitions_column(state: &State) -> Element<'_, Message> {
             match state
                 .packages
                 .as_ref()
-                .and_then(|p| p.get(addr))
+                .and_then(|p| p.get_package_by_address(addr))
                 .and_then(|pkg| pkg.modules.get(module_name))
             {
                 Some(module) => {
                     let definition_buttons =
                         build_definition_buttons(module, selected_definition, state.public_only);
-                    column(definition_buttons)
-                        .spacing(2)
-                        .width(Length::Fill)
-                        .into()
+                    let packages = state.packages.as_ref().expect("state.packages == None");
+                    column![
+                        button(text("Copy interface").size(14))
+                            .on_press(Message::CopyInterface(*addr, *module_name)),
+                        column(definition_buttons).spacing(2).width(Length::Fill),
+                        build_module_links(packages, module),
+                    ]
+                    .spacing(20)
+                    .into()
                 }
                 None => text("Module not found").size(14).into(),
             }
@@ -212,6 +315,54 @@ fn build_definitions_column(state: &State) -> Element<'_, Message> {
     build_column("Definitions", content)
 }
 
+/// "Depends on" and "Used by" lists of a module. Modules of packages that
+/// are not loaded are shown but cannot be opened.
+fn build_module_links<'a>(
+    packages: &'a crate::PackageTree,
+    module: &move_model_2::summary::Module,
+) -> Element<'a, Message> {
+    let key = (module.id.address, module.id.name);
+    let links = |modules: Option<&std::collections::BTreeSet<crate::ModuleKey>>| {
+        let links: Vec<Element<Message>> = modules
+            .into_iter()
+            .flatten()
+            .map(|key @ (address, name)| {
+                let label = text(packages.module_path(key)).size(14);
+                let loaded = packages
+                    .get_package_by_address(address)
+                    .is_some_and(|package| package.modules.contains_key(name));
+                if loaded {
+                    button(label)
+                        .on_press(Message::OpenModule(*address, *name))
+                        .style(default_button_style)
+                        .width(Length::Fill)
+                        .into()
+                } else {
+                    label.into()
+                }
+            })
+            .collect();
+        if links.is_empty() {
+            Element::from(text("none").size(14))
+        } else {
+            column(links).spacing(2).into()
+        }
+    };
+
+    let graph = packages.graph();
+    column![
+        text("Depends on").size(16),
+        rule::horizontal(1),
+        links(graph.dependencies(&key)),
+        text("Used by").size(16),
+        rule::horizontal(1),
+        links(graph.dependents(&key)),
+    ]
+    .spacing(6)
+    .width(Length::Fill)
+    .into()
+}
+
 fn build_definition_buttons(
     module: &move_model_2::summary::Module,
     selected_definition: Option<(DefType, Symbol)>,
@@ -301,15 +452,23 @@ fn build_json_column(state: &State) -> Element<'_, Message> {
             match state
                 .packages
                 .as_ref()
-                .and_then(|p| p.get(addr))
+                .and_then(|p| p.get_package_by_address(addr))
                 .and_then(|pkg| pkg.modules.get(module_name))
             {
                 Some(module) => {
                     let json_string = serialize_definition(module, def_type, def_name);
-                    container(text(json_string).size(16))
+                    let json = container(text(json_string).size(16))
                         .padding(5)
-                        .width(Length::Fill)
-                        .into()
+                        .width(Length::Fill);
+                    match def_type {
+                        DefType::Function => json.into(),
+                        DefType::Struct | DefType::Enum => {
+                            let packages = state.packages.as_ref().expect("state.packages == None");
+                            column![json, build_type_usages(packages, module, *def_name)]
+                                .spacing(20)
+                                .into()
+                        }
+                    }
                 }
                 None => text("Module not found").size(14).into(),
             }
@@ -320,151 +479,66 @@ fn build_json_column(state: &State) -> Element<'_, Message> {
     build_column("Definition JSON", content)
 }
 
-fn format_param(param: &move_model_2::summary::Parameter) -> String {
-    let name = param.name.expect("Parameter with no name");
-    let typename = type_to_string(&param.type_);
-    format!("{}: {}", name, typename)
-}
-
-fn build_function_signature(
-    def_name: &Symbol,
-    function: &move_model_2::summary::Function,
-) -> String {
-    let mut signature = String::new();
-    signature.push_str("fun ");
-    signature.push_str(&def_name.to_string());
-
-    // Add type parameters if any
-    if !function.type_parameters.is_empty() {
-        signature.push('<');
-        for (i, tparam) in function.type_parameters.iter().enumerate() {
-            if i > 0 {
-                signature.push_str(", ");
-            }
-            signature.push_str(&tparam.name.expect("tparam.name not found"));
-        }
-        signature.push('>');
-    }
-
-    // Add parameters
-    signature.push('(');
-    for (i, param) in function.parameters.iter().enumerate() {
-        if i > 0 {
-            signature.push_str(",\n    ");
-        } else {
-            signature.push_str("\n    ");
-        }
-        signature.push_str(&format_param(param));
-    }
-    if !function.parameters.is_empty() {
-        signature.push_str(",\n");
-    }
-    signature.push(')');
-
-    // Add return type if any
-    if !function.return_.is_empty() {
-        signature.push_str(": ");
-        if function.return_.len() == 1 {
-            signature.push_str(&type_to_string(&function.return_[0]));
-        } else {
-            signature.push('(');
-            for (i, ret_type) in function.return_.iter().enumerate() {
-                if i > 0 {
-                    signature.push_str(", ");
-                }
-                signature.push_str(&type_to_string(&ret_type));
-            }
-            signature.push(')');
-        }
-    }
-
-    signature
-}
-
-fn build_struct_signature(def_name: &Symbol, struct_def: &move_model_2::summary::Struct) -> String {
-    let mut signature = String::new();
-    signature.push_str("struct ");
-    signature.push_str(&def_name.to_string());
-
-    // Add type parameters if any
-    if !struct_def.type_parameters.is_empty() {
-        signature.push('<');
-        for (i, tparam) in struct_def.type_parameters.iter().enumerate() {
-            if i > 0 {
-                signature.push_str(", ");
-            }
-            signature.push_str(&tparam.tparam.name.expect("tparam.name not found"));
-        }
-        signature.push('>');
-    }
-
-    // Add fields
-    signature.push_str(" {\n");
-    for (field_name, field) in &struct_def.fields.fields {
-        signature.push_str(&format!(
-            "    {}: {},\n",
-            field_name,
-            type_to_string(&field.type_)
-        ));
-    }
-    signature.push('}');
-
-    signature
-}
-
-fn build_enum_signature(def_name: &Symbol, enum_def: &move_model_2::summary::Enum) -> String {
-    let mut signature = String::new();
-    signature.push_str("enum ");
-    signature.push_str(&def_name.to_string());
-
-    // Add type parameters if any
-    if !enum_def.type_parameters.is_empty() {
-        signature.push('<');
-        for (i, tparam) in enum_def.type_parameters.iter().enumerate() {
-            if i > 0 {
-                signature.push_str(", ");
-            }
-            signature.push_str(&tparam.tparam.name.expect("tparam.name not found"));
-        }
-        signature.push('>');
-    }
-
-    // Add variants
-    signature.push_str(" {\n");
-    for (variant_name, variant) in &enum_def.variants {
-        signature.push_str(&format!("    {}", variant_name));
-
-        // Add variant fields if any
-        if !variant.fields.fields.is_empty() {
-            if variant.fields.positional_fields {
-                // Positional fields: Variant(type1, type2, ...)
-                signature.push('(');
-                let field_types: Vec<String> = variant
-                    .fields
-                    .fields
-                    .values()
-                    .map(|field| type_to_string(&field.type_))
-                    .collect();
-                signature.push_str(&field_types.join(", "));
-                signature.push(')');
-            } else {
-                // Named fields: Variant { name1: type1, name2: type2, ... }
-                signature.push_str(" { ");
-                let field_strs: Vec<String> = variant
-                    .fields
-                    .fields
-                    .iter()
-                    .map(|(name, field)| format!("{}: {}", name, type_to_string(&field.type_)))
-                    .collect();
-                signature.push_str(&field_strs.join(", "));
-                signature.push_str(" }");
-            }
-        }
-        signature.push_str(",\n");
-    }
-    signature.push('}');
+/// "Used by" panel listing every parameter, return type and field that
+/// mentions the datatype `name`.
+fn build_type_usages<'a>(
+    packages: &'a crate::PackageTree,
+    module: &move_model_2::summary::Module,
+    name: Symbol,
+) -> Element<'a, Message> {
+    let usages: Vec<Element<Message>> = packages
+        .datatype_usages(module, name)
+        .iter()
+        .map(|usage| {
+            let module_path = packages.module_path(&(usage.address, usage.module));
+            let (def_type, def_name, label) = match &usage.site {
+                crate::UsageSite::Parameter { function, index } => (
+                    DefType::Function,
+                    *function,
+                    format!("{}::{} (parameter {})", module_path, function, index),
+                ),
+                crate::UsageSite::Return { function, index } => (
+                    DefType::Function,
+                    *function,
+                    format!("{}::{} (return {})", module_path, function, index),
+                ),
+                crate::UsageSite::Field { datatype, field } => (
+                    DefType::Struct,
+                    *datatype,
+                    format!("{}::{}.{}", module_path, datatype, field),
+                ),
+                crate::UsageSite::VariantField {
+                    datatype,
+                    variant,
+                    field,
+                } => (
+                    DefType::Enum,
+                    *datatype,
+                    format!("{}::{}::{}.{}", module_path, datatype, variant, field),
+                ),
+            };
+            button(text(label).size(14))
+                .on_press(Message::OpenDefinition(
+                    usage.address,
+                    usage.module,
+                    def_type,
+                    def_name,
+                ))
+                .style(default_button_style)
+                .width(Length::Fill)
+                .into()
+        })
+        .collect();
 
-    signature
+    let list: Element<Message> = if usages.is_empty() {
+        text("none").size(14).into()
+    } else {
+        column(usages).spacing(2).into()
+    };
+    column![text("Used by").size(16), rule::horizontal(1), list]
+        .spacing(6)
+        .width(Length::Fill)
+        .into()
 }
 
 fn serialize_definition(
@@ -475,7 +549,7 @@ fn serialize_definition(
     match def_type {
         DefType::Function => {
             if let Some(function) = module.functions.get(def_name) {
-                let signature = build_function_signature(def_name, function);
+                let signature = crate::render::render_function(*def_name, function);
                 let json_val = serde_json::to_string_pretty(function)
                     .unwrap_or_else(|_| "Error serializing function".to_string());
                 format!("{}\n\n{}", signature, json_val)
@@ -485,7 +559,7 @@ fn serialize_definition(
         }
         DefType::Struct => {
             if let Some(struct_def) = module.structs.get(def_name) {
-                let signature = build_struct_signature(def_name, struct_def);
+                let signature = crate::render::render_struct(*def_name, struct_def);
                 let json_val = serde_json::to_string_pretty(struct_def)
                     .unwrap_or_else(|_| "Error serializing struct".to_string());
                 format!("{}\n\n{}", signature, json_val)
@@ -495,7 +569,7 @@ fn serialize_definition(
         }
         DefType::Enum => {
             if let Some(enum_def) = module.enums.get(def_name) {
-                let signature = build_enum_signature(def_name, enum_def);
+                let signature = crate::render::render_enum(*def_name, enum_def);
                 let json_val = serde_json::to_string_pretty(enum_def)
                     .unwrap_or_else(|_| "Error serializing enum".to_string());
                 format!("{}\n\n{}", signature, json_val)
@@ -531,127 +605,47 @@ fn build_search_view(state: &State) -> Element<'_, Message> {
 
     let sui_checkbox = checkbox("sui", state.sui_filter).on_toggle(Message::ToggleSuiFilter);
 
-    let search_row = row![search_input, std_checkbox, sui_checkbox].spacing(10);
+    let exact_checkbox =
+        checkbox("exact signature", state.signature_exact).on_toggle(Message::ToggleSignatureExact);
 
-    let mut items = Vec::new();
-    let search_queries: Vec<String> = if state.search_input.trim().is_empty() {
-        vec![]
-    } else {
-        state
-            .search_input
-            .split(',')
-            .map(|s| s.trim().to_lowercase())
-            .filter(|s| !s.is_empty())
-            .collect()
-    };
-
-    let matches_search = |name: &str| -> bool {
-        if search_queries.is_empty() {
-            true
-        } else {
-            let name_lower = name.to_lowercase();
-            search_queries
-                .iter()
-                .all(|query| name_lower.contains(query))
-        }
-    };
-
-    if let Some(packages) = &state.packages {
-        for (package_addr, package) in packages {
-            if let Some(package_name) = &package.name {
-                if &package_name.to_string() == "sui" && !state.sui_filter {
-                    continue;
-                }
-                if &package_name.to_string() == "std" && !state.std_filter {
-                    continue;
-                }
-                for (module_name, module) in &package.modules {
-                    if matches_search(&module_name.to_string()) {
-                        items.push(SearchItem::Module {
-                            package_addr: *package_addr,
-                            module_name: *module_name,
-                            display: format!("Module: {}.{}", package_name, module_name),
-                        });
-                    }
-
-                    for function_name in module.functions.keys() {
-                        if matches_search(&function_name.to_string()) {
-                            items.push(SearchItem::Definition {
-                                package_addr: *package_addr,
-                                module_name: *module_name,
-                                def_type: DefType::Function,
-                                def_name: *function_name,
-                                display: format!(
-                                    "  Function: {}.{}.{}",
-                                    package_name, module_name, function_name
-                                ),
-                            });
-                        }
-                    }
+    let docs_checkbox = checkbox("docs", state.docs_search).on_toggle(Message::ToggleDocsSearch);
 
-                    for struct_name in module.structs.keys() {
-                        if matches_search(&struct_name.to_string()) {
-                            items.push(SearchItem::Definition {
-                                package_addr: *package_addr,
-                                module_name: *module_name,
-                                def_type: DefType::Struct,
-                                def_name: *struct_name,
-                                display: format!(
-                                    "  Struct: {}.{}.{}",
-                                    package_name, module_name, struct_name
-                                ),
-                            });
-                        }
-                    }
+    let search_row = row![
+        search_input,
+        std_checkbox,
+        sui_checkbox,
+        exact_checkbox,
+        docs_checkbox
+    ]
+    .spacing(10);
 
-                    for enum_name in module.enums.keys() {
-                        if matches_search(&enum_name.to_string()) {
-                            items.push(SearchItem::Definition {
-                                package_addr: *package_addr,
-                                module_name: *module_name,
-                                def_type: DefType::Enum,
-                                def_name: *enum_name,
-                                display: format!(
-                                    "  Enum: {}.{}.{}",
-                                    package_name, module_name, enum_name
-                                ),
-                            });
-                        }
-                    }
-                }
-            }
-        }
+    if let Some(err) = &state.search_error {
+        return column![
+            search_row,
+            text(err).size(14).color(Color::from_rgb(0.9, 0.3, 0.3))
+        ]
+        .spacing(10)
+        .padding(10)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into();
     }
 
-    let items_list: Vec<Element<Message>> = items
-        .into_iter()
-        .map(|item| match item {
-            SearchItem::Module {
-                module_name,
-                display,
-                package_addr,
-                ..
-            } => button(text(display).size(12))
-                .on_press(Message::SelectFromSearch(package_addr, module_name, None))
-                .style(default_button_style)
-                .width(Length::Fill)
-                .into(),
-            SearchItem::Definition {
-                module_name,
-                def_type,
-                def_name,
-                display,
-                package_addr,
-                ..
-            } => button(text(display).size(12))
+    let items_list: Vec<Element<Message>> = state
+        .search_results
+        .iter()
+        .map(|result| {
+            let mut spans = vec![span(result.label)];
+            spans.extend(highlighted_spans(&result.path, &result.positions));
+            button(rich_text(spans).size(12))
                 .on_press(Message::SelectFromSearch(
-                    package_addr,
-                    module_name,
-                    Some((def_type, def_name)),
+                    result.package_addr,
+                    result.module_name,
+                    result.definition,
                 ))
                 .style(default_button_style)
                 .width(Length::Fill)
-                .into(),
+                .into()
         })
         .collect();
 
@@ -667,3 +661,31 @@ fn build_search_view(state: &State) -> Element<'_, Message> {
     .height(Length::Fill)
     .into()
 }
+
+/// Split `text` into spans, coloring the characters at `positions`.
+fn highlighted_spans<'a>(text: &'a str, positions: &[usize]) -> Vec<Span<'a>> {
+    let highlight = Color::from_rgb(0.98, 0.75, 0.3);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut highlighted = false;
+    for (i, (byte, _)) in text.char_indices().enumerate() {
+        let is_match = positions.binary_search(&i).is_ok();
+        if is_match != highlighted && byte > start {
+            let part = span(&text[start..byte]);
+            spans.push(if highlighted {
+                part.color(highlight)
+            } else {
+                part
+            });
+            start = byte;
+        }
+        highlighted = is_match;
+    }
+    let part = span(&text[start..]);
+    spans.push(if highlighted {
+        part.color(highlight)
+    } else {
+        part
+    });
+    spans
+}
@@ -0,0 +1,166 @@
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::Packages;
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The kind of a definition inside a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DefinitionKind {
+    Function,
+    Struct,
+    Enum,
+}
+
+/// Location of a definition, usable to fetch it from `Packages` without scanning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefinitionRef {
+    pub address: AccountAddress,
+    pub module: Symbol,
+    pub kind: DefinitionKind,
+    pub name: Symbol,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ModuleEntry {
+    /// Key of the module in `Package::modules`.
+    key: Symbol,
+    definitions: HashMap<String, (DefinitionKind, Symbol)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PackageEntry {
+    address: AccountAddress,
+    modules: HashMap<String, ModuleEntry>,
+}
+
+/// Name based lookup tables built once from a set of `Packages`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PackageIndex {
+    packages: HashMap<String, PackageEntry>,
+    names: HashMap<AccountAddress, String>,
+}
+
+impl PackageIndex {
+    pub fn new(packages: &Packages) -> Self {
+        let mut index = Self::default();
+
+        for (address, package) in &packages.packages {
+            let Some(package_name) = package.name else {
+                continue;
+            };
+            // The lowest address keeps the name, see
+            // `Diagnostic::DuplicatePackageName`
+            if index.packages.contains_key(package_name.as_str()) {
+                continue;
+            }
+
+            let mut modules = HashMap::new();
+            for (key, module) in &package.modules {
+                let mut definitions = HashMap::new();
+                // Functions take precedence over structs, and structs over enums,
+                // matching the lookup order of `PackageTree::get_definition`.
+                let entries = module
+                    .functions
+                    .keys()
+                    .map(|name| (DefinitionKind::Function, *name))
+                    .chain(
+                        module
+                            .structs
+                            .keys()
+                            .map(|name| (DefinitionKind::Struct, *name)),
+                    )
+                    .chain(
+                        module
+                            .enums
+                            .keys()
+                            .map(|name| (DefinitionKind::Enum, *name)),
+                    );
+                for (kind, name) in entries {
+                    definitions.entry(name.to_string()).or_insert((kind, name));
+                }
+
+                modules.insert(
+                    module.id.name.to_string(),
+                    ModuleEntry {
+                        key: *key,
+                        definitions,
+                    },
+                );
+            }
+
+            index.names.insert(*address, package_name.to_string());
+            index.packages.insert(
+                package_name.to_string(),
+                PackageEntry {
+                    address: *address,
+                    modules,
+                },
+            );
+        }
+
+        index
+    }
+
+    /// Address of the package with the given name.
+    pub fn package_address(&self, package: &str) -> Option<AccountAddress> {
+        self.packages.get(package).map(|entry| entry.address)
+    }
+
+    /// Name of the package at the given address.
+    pub fn package_name(&self, address: &AccountAddress) -> Option<&str> {
+        self.names.get(address).map(String::as_str)
+    }
+
+    /// Address of the package and key of the module inside `Package::modules`.
+    pub fn module(&self, package: &str, module: &str) -> Option<(AccountAddress, Symbol)> {
+        let entry = self.packages.get(package)?;
+        let module = entry.modules.get(module)?;
+        Some((entry.address, module.key))
+    }
+
+    /// Names of all indexed packages, in no particular order.
+    pub fn package_names(&self) -> impl Iterator<Item = &str> {
+        self.packages.keys().map(String::as_str)
+    }
+
+    /// Names of the modules of a package, in no particular order.
+    pub fn module_names(&self, package: &str) -> impl Iterator<Item = &str> {
+        self.packages
+            .get(package)
+            .into_iter()
+            .flat_map(|entry| entry.modules.keys().map(String::as_str))
+    }
+
+    /// Names of the definitions of a module, in no particular order.
+    pub fn definition_names(&self, package: &str, module: &str) -> impl Iterator<Item = &str> {
+        self.packages
+            .get(package)
+            .and_then(|entry| entry.modules.get(module))
+            .into_iter()
+            .flat_map(|module| module.definitions.keys().map(String::as_str))
+    }
+
+    pub fn definition(&self, package: &str, module: &str, name: &str) -> Option<DefinitionRef> {
+        let entry = self.packages.get(package)?;
+        let module = entry.modules.get(module)?;
+        let (kind, name) = module.definitions.get(name)?;
+        Some(DefinitionRef {
+            address: entry.address,
+            module: module.key,
+            kind: *kind,
+            name: *name,
+        })
+    }
+
+    /// Resolve a fully qualified `pkg::module::item` path.
+    pub fn resolve(&self, path: &str) -> Option<DefinitionRef> {
+        let mut parts = path.split("::");
+        let (Some(package), Some(module), Some(name), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        self.definition(package, module, name)
+    }
+}
@@ -1,21 +1,353 @@
+pub mod bytecode;
+mod cache;
+pub mod docs;
+pub mod graph;
 pub mod gui;
+pub mod index;
 pub mod parser;
+pub mod reload;
+pub mod render;
+pub mod search;
 pub mod service;
+pub mod signature;
+mod source;
+pub mod suggest;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod usages;
+pub mod validate;
 
-use std::sync::Arc;
+use move_core_types::account_address::AccountAddress;
+use move_symbol_pool::Symbol;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+pub use docs::{DocHit, DocIndex, DocTarget};
+pub use graph::{ModuleGraph, ModuleKey};
+pub use index::{DefinitionKind, DefinitionRef, PackageIndex};
+pub use parser::{Diagnostic, ParseMode, RootPackageMetadata, SchemaVersion, SummaryError};
+pub use search::{ItemKind, SearchEntry, SearchHit, SearchIndex};
+pub use signature::{MatchMode, QueryError, SignatureQuery};
+pub use usages::{DatatypeKey, TypeUsage, TypeUsageIndex, UsageSite};
+pub use validate::ValidationIssue;
 
 #[derive(Clone)]
 pub struct PackageTree {
     packages: Arc<move_model_2::summary::Packages>,
+    index: Arc<PackageIndex>,
+    graph: Arc<ModuleGraph>,
+    usages: Arc<TypeUsageIndex>,
+    docs: Arc<DocIndex>,
+    search: Arc<SearchIndex>,
+    metadata: Option<Arc<parser::RootPackageMetadata>>,
+    root_package: Option<AccountAddress>,
+    diagnostics: Arc<Vec<parser::Diagnostic>>,
+}
+
+impl std::fmt::Debug for PackageTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PackageTree")
+            .field("packages", &self.packages.packages.len())
+            .field("root_package", &self.root_package)
+            .field("diagnostics", &self.diagnostics.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// How `PackageTree::load` reads a summaries folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub mode: ParseMode,
+    /// Reuse a fresh binary cache next to the folder, and write one after
+    /// parsing. Trees with diagnostics are never cached.
+    pub cache: bool,
+}
+
+/// Parse `dir` as a summaries folder or archive, or as compiled bytecode when
+/// it only holds `.mv` modules.
+fn parse(
+    dir: &std::path::Path,
+    mode: ParseMode,
+) -> Result<parser::ParsedSummaries, parser::SummaryError> {
+    if bytecode::is_bytecode_dir(dir) {
+        bytecode::parse_bytecode(dir, mode)
+    } else {
+        parser::parse_summaries_with(dir, mode)
+    }
 }
 
 impl PackageTree {
-    pub fn new(dir: &std::path::Path) -> anyhow::Result<Self> {
-        let pkg_path = std::path::Path::new(dir);
-        let packages = parser::parse_summaries(pkg_path)?;
-        Ok(Self {
+    pub fn new(dir: &std::path::Path) -> Result<Self, parser::SummaryError> {
+        Self::with_mode(dir, parser::ParseMode::Strict)
+    }
+    pub fn with_mode(
+        dir: &std::path::Path,
+        mode: parser::ParseMode,
+    ) -> Result<Self, parser::SummaryError> {
+        Self::load(dir, LoadOptions { mode, cache: false })
+    }
+    pub fn load(dir: &std::path::Path, options: LoadOptions) -> Result<Self, parser::SummaryError> {
+        if !options.cache {
+            let parsed = parse(dir, options.mode)?;
+            let indexes = cache::Indexes::new(&parsed.packages);
+            return Ok(Self::build(
+                parsed.packages,
+                indexes,
+                parsed.metadata,
+                parsed.diagnostics,
+            ));
+        }
+
+        if let Some(cached) = cache::load(dir) {
+            return Ok(Self::build(
+                cached.packages,
+                cached.indexes,
+                cached.metadata,
+                Vec::new(),
+            ));
+        }
+
+        // Stamp before parsing so that changes made while parsing invalidate
+        // the cache
+        let stamps = cache::collect_stamps(dir);
+        let parsed = parse(dir, options.mode)?;
+        let indexes = cache::Indexes::new(&parsed.packages);
+        if !parsed.diagnostics.is_empty() {
+            return Ok(Self::build(
+                parsed.packages,
+                indexes,
+                parsed.metadata,
+                parsed.diagnostics,
+            ));
+        }
+
+        let key = stamps.and_then(|stamps| cache::CacheKey::new(dir, stamps, &parsed.file_hashes));
+        let cached = cache::Cached {
+            packages: parsed.packages,
+            metadata: parsed.metadata,
+            indexes,
+        };
+        if let Err(err) = key.and_then(|key| cache::store(dir, &key, &cached)) {
+            eprintln!("Failed to write summary cache: {}", err);
+        }
+        Ok(Self::build(
+            cached.packages,
+            cached.indexes,
+            cached.metadata,
+            Vec::new(),
+        ))
+    }
+    /// Load several summaries folders into one tree. Packages are
+    /// deduplicated by address, and a package whose modules differ between
+    /// folders is reported as a `Diagnostic::ConflictingModule`.
+    pub fn load_many<P: AsRef<std::path::Path>>(
+        dirs: &[P],
+        options: LoadOptions,
+    ) -> Result<Self, parser::SummaryError> {
+        if let [dir] = dirs {
+            return Self::load(dir.as_ref(), options);
+        }
+
+        let mut packages = BTreeMap::new();
+        let mut origins: BTreeMap<AccountAddress, &std::path::Path> = BTreeMap::new();
+        let mut metadata = None;
+        let mut diagnostics = Vec::new();
+
+        for dir in dirs {
+            let dir = dir.as_ref();
+            let tree = Self::load(dir, options)?;
+            metadata = metadata.or(tree.metadata.map(Arc::unwrap_or_clone));
+            // Duplicate names are reported again for the merged packages
+            let tree_diagnostics = Arc::into_inner(tree.diagnostics).unwrap_or_default();
+            diagnostics.extend(tree_diagnostics.into_iter().filter(|diagnostic| {
+                !matches!(diagnostic, parser::Diagnostic::DuplicatePackageName { .. })
+            }));
+
+            for (address, package) in Arc::unwrap_or_clone(tree.packages).packages {
+                let Some(existing) = packages.get(&address) else {
+                    packages.insert(address, package);
+                    origins.insert(address, dir);
+                    continue;
+                };
+                for module in conflicting_modules(existing, &package) {
+                    diagnostics.push(parser::Diagnostic::ConflictingModule {
+                        address,
+                        package: package
+                            .name
+                            .map_or_else(String::new, |name| name.to_string()),
+                        module: module.to_string(),
+                        kept: origins[&address].to_path_buf(),
+                        ignored: dir.to_path_buf(),
+                    });
+                }
+            }
+        }
+
+        let packages = move_model_2::summary::Packages { packages };
+        let indexes = cache::Indexes::new(&packages);
+        Ok(Self::build(packages, indexes, metadata, diagnostics))
+    }
+    pub fn from_packages(packages: move_model_2::summary::Packages) -> Self {
+        let indexes = cache::Indexes::new(&packages);
+        Self::build(packages, indexes, None, Vec::new())
+    }
+    fn build(
+        packages: move_model_2::summary::Packages,
+        indexes: cache::Indexes,
+        metadata: Option<parser::RootPackageMetadata>,
+        mut diagnostics: Vec<parser::Diagnostic>,
+    ) -> Self {
+        let root_package = find_root_package(&packages, metadata.as_ref());
+        diagnostics.extend(duplicate_package_names(&packages, &indexes.index));
+        Self {
             packages: Arc::new(packages),
-        })
+            index: Arc::new(indexes.index),
+            graph: Arc::new(indexes.graph),
+            usages: Arc::new(indexes.usages),
+            docs: Arc::new(indexes.docs),
+            search: Arc::new(indexes.search),
+            metadata: metadata.map(Arc::new),
+            root_package,
+            diagnostics: Arc::new(diagnostics),
+        }
+    }
+    pub fn packages(&self) -> &BTreeMap<AccountAddress, move_model_2::summary::Package> {
+        &self.packages.packages
+    }
+    pub fn index(&self) -> &PackageIndex {
+        &self.index
+    }
+    /// Module dependency graph of all loaded modules.
+    pub fn graph(&self) -> &ModuleGraph {
+        &self.graph
+    }
+    /// Graph node of a module given by package and module name.
+    pub fn module_key(&self, package: &str, module: &str) -> Option<ModuleKey> {
+        let module = self.get_module(package, module)?;
+        Some((module.id.address, module.id.name))
+    }
+    /// `package::module` for a graph node, falling back to the address for
+    /// modules of packages that are not loaded.
+    pub fn module_path(&self, (address, module): &ModuleKey) -> String {
+        match self.index.package_name(address) {
+            Some(package) => format!("{}::{}", package, module),
+            None => format!("{}::{}", address.to_hex_literal(), module),
+        }
+    }
+    /// Move interface of a loaded module, see
+    /// [`render::render_module_interface`].
+    pub fn module_interface(&self, (address, module): &ModuleKey) -> Option<String> {
+        let module = self.get_package_by_address(address)?.modules.get(module)?;
+        Some(render::render_module_interface(module, |key| {
+            self.module_path(key)
+        }))
+    }
+    /// Parameters, return types and fields mentioning a datatype given as
+    /// `package::module::Type`, where the package is a name or an address.
+    /// `None` if the path does not name a loaded definition.
+    pub fn type_usages(&self, datatype: &str) -> Option<&[TypeUsage]> {
+        let mut parts = datatype.split("::");
+        let (Some(package), Some(module), Some(name), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        let package = match AccountAddress::from_hex_literal(package) {
+            Ok(address) => self.index.package_name(&address)?,
+            Err(_) => package,
+        };
+        let def = self.index.definition(package, module, name)?;
+        let module = self
+            .get_package_by_address(&def.address)?
+            .modules
+            .get(&def.module)?;
+        Some(self.datatype_usages(module, def.name))
+    }
+    /// Uses of the datatype `name` defined in `module`.
+    pub fn datatype_usages(
+        &self,
+        module: &move_model_2::summary::Module,
+        name: Symbol,
+    ) -> &[TypeUsage] {
+        self.usages
+            .usages(&(module.id.address, module.id.name, name))
+    }
+    /// Functions whose parameters and return types have the shape of `query`,
+    /// in package, module and name order.
+    pub fn search_by_signature(
+        &self,
+        query: &SignatureQuery,
+        mode: MatchMode,
+    ) -> Vec<DefinitionRef> {
+        self.packages
+            .packages
+            .iter()
+            .flat_map(move |(address, package)| {
+                package
+                    .modules
+                    .iter()
+                    .flat_map(move |(module_key, module)| {
+                        module
+                            .functions
+                            .iter()
+                            .filter(move |(_, function)| query.matches(&self.index, function, mode))
+                            .map(move |(name, _)| DefinitionRef {
+                                address: *address,
+                                module: *module_key,
+                                kind: DefinitionKind::Function,
+                                name: *name,
+                            })
+                    })
+            })
+            .collect()
+    }
+    /// Fuzzy name index over all modules and definitions.
+    pub fn search_index(&self) -> &SearchIndex {
+        &self.search
+    }
+    /// Modules, definitions, variants and fields accepted by `filter` whose
+    /// names or doc comments match the words of `query`, best first.
+    pub fn search_docs(
+        &self,
+        query: &str,
+        filter: impl Fn(&DocHit) -> bool,
+        limit: usize,
+    ) -> Vec<DocHit> {
+        self.docs.search(query, filter, limit)
+    }
+    /// The function a `DefinitionRef` of kind `Function` points at.
+    pub fn get_function_by_ref(
+        &self,
+        def: &DefinitionRef,
+    ) -> Option<&move_model_2::summary::Function> {
+        self.get_package_by_address(&def.address)?
+            .modules
+            .get(&def.module)?
+            .functions
+            .get(&def.name)
+    }
+    /// Contents of root_package_metadata.json, if the folder had one.
+    pub fn metadata(&self) -> Option<&parser::RootPackageMetadata> {
+        self.metadata.as_deref()
+    }
+    /// Address of the package the summaries were generated for.
+    pub fn root_package(&self) -> Option<AccountAddress> {
+        self.root_package
+    }
+    pub fn is_root_package(&self, address: &AccountAddress) -> bool {
+        self.root_package.as_ref() == Some(address)
+    }
+    /// Packages, modules and files that were skipped while parsing.
+    pub fn diagnostics(&self) -> &[parser::Diagnostic] {
+        &self.diagnostics
+    }
+    /// Type references, module dependencies and module addresses that do
+    /// not resolve within the loaded packages, typically because a summary
+    /// export is missing some packages.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        validate::validate(&self.packages)
     }
     pub fn list_packages(&self) -> Vec<String> {
         let pkg = self
@@ -27,34 +359,33 @@ impl PackageTree {
             .collect();
         pkg
     }
-    pub fn list_modules(&self, package: &str) -> Vec<String> {
-        let pkg = self.get_package(package).unwrap();
+    /// Module names of a package, `None` if there is no such package.
+    pub fn list_modules(&self, package: &str) -> Option<Vec<String>> {
+        let pkg = self.get_package(package)?;
         let ms = pkg
             .modules
             .values()
             .map(|pkg| pkg.id.name.to_string())
             .collect();
-        ms
+        Some(ms)
     }
     pub fn get_package<'a>(&'a self, package: &str) -> Option<&'a move_model_2::summary::Package> {
-        let pkg = self
-            .packages
-            .packages
-            .values()
-            .find(|pkg| pkg.name.map_or(false, |name| name.to_string() == package));
-        pkg
+        let address = self.index.package_address(package)?;
+        self.get_package_by_address(&address)
+    }
+    pub fn get_package_by_address<'a>(
+        &'a self,
+        address: &AccountAddress,
+    ) -> Option<&'a move_model_2::summary::Package> {
+        self.packages.packages.get(address)
     }
     pub fn get_module<'a>(
         &'a self,
         package: &str,
         module: &str,
     ) -> Option<&'a move_model_2::summary::Module> {
-        let pkg = self.get_package(package)?;
-        let res = pkg
-            .modules
-            .values()
-            .find(|module_| module_.id.name.to_string() == module);
-        res
+        let (address, key) = self.index.module(package, module)?;
+        self.get_package_by_address(&address)?.modules.get(&key)
     }
     pub fn get_function<'a>(
         &'a self,
@@ -62,11 +393,12 @@ impl PackageTree {
         module: &str,
         function_name: &str,
     ) -> Option<&'a move_model_2::summary::Function> {
-        let mdd = self.get_module(package, module)?;
-        mdd.functions
-            .iter()
-            .find(|(name, _)| name.to_string() == function_name)
-            .map(|(_, v)| v)
+        let def = self.find_definition(package, module, function_name)?;
+        self.get_package_by_address(&def.address)?
+            .modules
+            .get(&def.module)?
+            .functions
+            .get(&def.name)
     }
     pub fn get_enum<'a>(
         &'a self,
@@ -74,11 +406,12 @@ impl PackageTree {
         module: &str,
         enum_name: &str,
     ) -> Option<&'a move_model_2::summary::Enum> {
-        let mdd = self.get_module(package, module)?;
-        mdd.enums
-            .iter()
-            .find(|(name, _)| name.to_string() == enum_name)
-            .map(|(_, v)| v)
+        let def = self.find_definition(package, module, enum_name)?;
+        self.get_package_by_address(&def.address)?
+            .modules
+            .get(&def.module)?
+            .enums
+            .get(&def.name)
     }
     pub fn get_struct<'a>(
         &'a self,
@@ -86,11 +419,21 @@ impl PackageTree {
         module: &str,
         struct_name: &str,
     ) -> Option<&'a move_model_2::summary::Struct> {
-        let mdd = self.get_module(package, module)?;
-        mdd.structs
-            .iter()
-            .find(|(name, _)| name.to_string() == struct_name)
-            .map(|(_, v)| v)
+        let def = self.find_definition(package, module, struct_name)?;
+        self.get_package_by_address(&def.address)?
+            .modules
+            .get(&def.module)?
+            .structs
+            .get(&def.name)
+    }
+    /// Look up a definition through the index without knowing its kind.
+    pub fn find_definition(
+        &self,
+        package: &str,
+        module: &str,
+        definition_name: &str,
+    ) -> Option<DefinitionRef> {
+        self.index.definition(package, module, definition_name)
     }
     pub fn get_definition(
         &self,
@@ -98,16 +441,179 @@ impl PackageTree {
         module: &str,
         definition_name: &str,
     ) -> anyhow::Result<serde_json::Value> {
-        let definition = if let Some(val) = self.get_function(package, module, definition_name) {
-            serde_json::to_value(val)?
-        } else if let Some(val) = self.get_struct(package, module, definition_name) {
-            serde_json::to_value(val)?
-        } else if let Some(val) = self.get_enum(package, module, definition_name) {
-            serde_json::to_value(val)?
-        } else {
+        let Some(def) = self.find_definition(package, module, definition_name) else {
             anyhow::bail!("definition not found")
         };
+        let module = self
+            .get_package_by_address(&def.address)
+            .and_then(|pkg| pkg.modules.get(&def.module))
+            .ok_or_else(|| anyhow::anyhow!("definition not found"))?;
+
+        let definition = match def.kind {
+            DefinitionKind::Function => serde_json::to_value(&module.functions[&def.name])?,
+            DefinitionKind::Struct => serde_json::to_value(&module.structs[&def.name])?,
+            DefinitionKind::Enum => serde_json::to_value(&module.enums[&def.name])?,
+        };
 
         Ok(definition)
     }
 }
+
+/// Packages whose name is taken by a package at a lower address, and so
+/// missing from `index`.
+fn duplicate_package_names(
+    packages: &move_model_2::summary::Packages,
+    index: &PackageIndex,
+) -> Vec<parser::Diagnostic> {
+    packages
+        .packages
+        .iter()
+        .filter_map(|(address, package)| {
+            let name = package.name?;
+            let kept = index.package_address(name.as_str())?;
+            (kept != *address).then(|| parser::Diagnostic::DuplicatePackageName {
+                name: name.to_string(),
+                kept,
+                ignored: *address,
+            })
+        })
+        .collect()
+}
+
+/// The root package is the one named by root_package_metadata.json, or
+/// failing that the only loaded package that no other package depends on.
+fn find_root_package(
+    packages: &move_model_2::summary::Packages,
+    metadata: Option<&parser::RootPackageMetadata>,
+) -> Option<AccountAddress> {
+    let from_metadata = metadata.and_then(|metadata| {
+        [
+            metadata.root_package_id(),
+            metadata.root_package_original_id(),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|address| packages.packages.contains_key(address))
+    });
+    if from_metadata.is_some() {
+        return from_metadata;
+    }
+
+    let depended_on: BTreeSet<AccountAddress> = packages
+        .packages
+        .iter()
+        .flat_map(|(address, package)| {
+            package
+                .modules
+                .values()
+                .flat_map(|module| module.immediate_dependencies.iter())
+                .map(|dep| dep.address)
+                .filter(move |dep| dep != address)
+        })
+        .collect();
+
+    let mut roots = packages
+        .packages
+        .keys()
+        .filter(|address| !depended_on.contains(address));
+    match (roots.next(), roots.next()) {
+        (Some(root), None) => Some(*root),
+        _ => None,
+    }
+}
+
+/// Names of the modules that are missing from one of the packages or whose
+/// content differs between them.
+fn conflicting_modules(
+    a: &move_model_2::summary::Package,
+    b: &move_model_2::summary::Package,
+) -> Vec<Symbol> {
+    let names: BTreeSet<Symbol> = a.modules.keys().chain(b.modules.keys()).copied().collect();
+    names
+        .into_iter()
+        .filter(|name| match (a.modules.get(name), b.modules.get(name)) {
+            (Some(a), Some(b)) => serde_json::to_value(a).ok() != serde_json::to_value(b).ok(),
+            _ => true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TempDir, pool_module};
+    use std::{fs, path::PathBuf};
+
+    /// A summaries folder `name` in `dir` with one `pools` package at
+    /// `address` holding `modules`.
+    fn summaries(
+        dir: &TempDir,
+        name: &str,
+        address: &str,
+        modules: &[move_model_2::summary::Module],
+    ) -> PathBuf {
+        let root = dir.path().join(name);
+        fs::create_dir_all(root.join("pools")).unwrap();
+        let mapping = format!(r#"{{"pools": "{}"}}"#, address);
+        fs::write(root.join("address_mapping.json"), mapping).unwrap();
+        for module in modules {
+            let path = root.join("pools").join(format!("{}.json", module.id.name));
+            fs::write(path, serde_json::to_string(module).unwrap()).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn keeps_the_first_folder_of_packages_sharing_an_address() {
+        let dir = TempDir::new("load-many-conflict");
+        let first = summaries(&dir, "first", "0x2", &[pool_module()]);
+        let mut changed = pool_module();
+        changed.doc = Some(" Another pool.".to_string());
+        let mut extra = pool_module();
+        extra.id.name = Symbol::from("extra");
+        let second = summaries(&dir, "second", "0x2", &[changed, extra]);
+
+        let tree = PackageTree::load_many(&[&first, &second], LoadOptions::default()).unwrap();
+        let conflicts: Vec<_> = tree
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| match diagnostic {
+                Diagnostic::ConflictingModule {
+                    package,
+                    module,
+                    kept,
+                    ignored,
+                    ..
+                } if *kept == first && *ignored == second => format!("{package}::{module}"),
+                other => panic!("unexpected diagnostic: {other}"),
+            })
+            .collect();
+        assert_eq!(conflicts, ["pools::extra", "pools::pool"]);
+
+        let pool = tree.get_module("pools", "pool").unwrap();
+        assert_eq!(pool.doc.as_deref(), Some(" A pool of balances."));
+        assert!(tree.get_module("pools", "extra").is_none());
+    }
+
+    #[test]
+    fn reports_duplicate_names_across_folders_once() {
+        let dir = TempDir::new("load-many-names");
+        let first = summaries(&dir, "first", "0x2", &[pool_module()]);
+        let second = summaries(&dir, "second", "0x3", &[pool_module()]);
+
+        let tree = PackageTree::load_many(&[&first, &second], LoadOptions::default()).unwrap();
+        let two = AccountAddress::from_hex_literal("0x2").unwrap();
+        let three = AccountAddress::from_hex_literal("0x3").unwrap();
+        assert!(matches!(
+            tree.diagnostics(),
+            [Diagnostic::DuplicatePackageName { name, kept, ignored }]
+                if name == "pools" && *kept == two && *ignored == three
+        ));
+        assert_eq!(
+            tree.get_package("pools")
+                .map(|package| package.modules.len()),
+            Some(1)
+        );
+        assert!(tree.get_package_by_address(&three).is_some());
+    }
+}
@@ -1,204 +1,975 @@
-use anyhow::{Context, Result, anyhow};
 use move_core_types::account_address::AccountAddress;
 use move_symbol_pool::Symbol;
+use rayon::prelude::*;
 use serde::Deserialize;
-use std::{collections::BTreeMap, fs, path::Path};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::source::Source;
 
 // Constants from move-cli/src/base/summary.rs
 const ADDRESS_MAPPING_FILENAME: &str = "address_mapping";
 const METADATA_FILENAME: &str = "root_package_metadata";
 const JSON_EXT: &str = "json";
 
+/// Errors that can occur while reading a `package_summaries` directory or a
+/// folder of compiled modules.
+///
+/// Every variant carries the path it relates to, and JSON errors carry the
+/// 1-based line and column reported by the parser.
+#[derive(Debug, thiserror::Error)]
+pub enum SummaryError {
+    #[error("Summaries directory does not exist: {}", path.display())]
+    MissingDirectory { path: PathBuf },
+    #[error("Path is not a directory: {}", path.display())]
+    NotADirectory { path: PathBuf },
+    #[error("Address mapping file not found: {}", path.display())]
+    MissingAddressMapping { path: PathBuf },
+    #[error(
+        "Failed to parse address '{address}' for symbol '{symbol}' at {}:{line}:{column}",
+        path.display()
+    )]
+    InvalidAddress {
+        path: PathBuf,
+        symbol: String,
+        address: String,
+        line: usize,
+        column: usize,
+    },
+    #[error("Package '{package}' not found in address_mapping.json: {}", path.display())]
+    UnknownPackage { path: PathBuf, package: String },
+    #[error("Failed to read archive {}: {source}", path.display())]
+    InvalidArchive {
+        path: PathBuf,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Invalid file name: {}", path.display())]
+    InvalidFileName { path: PathBuf },
+    #[error("Failed to read {}: {source}", path.display())]
+    Unreadable {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to parse JSON from {}:{line}:{column}: {source}", path.display())]
+    InvalidJson {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Failed to deserialize bytecode module {}: {message}", path.display())]
+    InvalidBytecode { path: PathBuf, message: String },
+    #[error(
+        "Unsupported summary schema {} in {} (supported up to version {})",
+        version.map_or_else(|| "layout".to_string(), |version| format!("version {}", version)),
+        path.display(),
+        SchemaVersion::CURRENT as u8
+    )]
+    UnsupportedSchema {
+        path: PathBuf,
+        /// `None` when the file states no version but has keys no supported
+        /// version knows.
+        version: Option<u64>,
+    },
+    #[error("Module JSON does not match the summary schema at {}:{line}:{column}: {source}", path.display())]
+    ModuleSchema {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+impl SummaryError {
+    /// The file or directory the error relates to.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::MissingDirectory { path }
+            | Self::NotADirectory { path }
+            | Self::MissingAddressMapping { path }
+            | Self::InvalidAddress { path, .. }
+            | Self::UnknownPackage { path, .. }
+            | Self::InvalidArchive { path, .. }
+            | Self::InvalidFileName { path }
+            | Self::Unreadable { path, .. }
+            | Self::InvalidBytecode { path, .. }
+            | Self::UnsupportedSchema { path, .. }
+            | Self::InvalidJson { path, .. }
+            | Self::ModuleSchema { path, .. } => path,
+        }
+    }
+
+    /// The 1-based line and column inside the file, for JSON related errors.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::InvalidAddress { line, column, .. }
+            | Self::InvalidJson { line, column, .. }
+            | Self::ModuleSchema { line, column, .. } => Some((*line, *column)),
+            _ => None,
+        }
+    }
+
+    fn invalid_json(path: &Path) -> impl FnOnce(serde_json::Error) -> Self {
+        move |source| Self::InvalidJson {
+            path: path.to_path_buf(),
+            line: source.line(),
+            column: source.column(),
+            source,
+        }
+    }
+}
+
+pub type Result<T, E = SummaryError> = std::result::Result<T, E>;
+
+/// How parsing reacts to malformed packages and modules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail on the first malformed package or module.
+    #[default]
+    Strict,
+    /// Skip malformed packages and modules and record a `Diagnostic` instead.
+    Lenient,
+}
+
+/// Something that was skipped while parsing a summaries directory.
+#[derive(Debug)]
+pub enum Diagnostic {
+    /// A package directory that was not loaded.
+    SkippedPackage(SummaryError),
+    /// A module file that could not be read or parsed.
+    SkippedModule(SummaryError),
+    /// A file that is not part of the summary layout.
+    StrayFile { path: PathBuf },
+    /// A module whose package was loaded from several folders with differing
+    /// content. The copy from `kept` is used.
+    ConflictingModule {
+        address: AccountAddress,
+        package: String,
+        module: String,
+        kept: PathBuf,
+        ignored: PathBuf,
+    },
+    /// A compiled module at the address of an already loaded package with a
+    /// different name, typically two unpublished packages at `0x0`.
+    SharedAddress {
+        address: AccountAddress,
+        kept: String,
+        ignored: String,
+        path: PathBuf,
+    },
+    /// Packages at different addresses with the same name. Only `kept` can
+    /// be looked up by name, the others by address.
+    DuplicatePackageName {
+        name: String,
+        kept: AccountAddress,
+        ignored: AccountAddress,
+    },
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SkippedPackage(err) => write!(f, "Skipped package: {}", err),
+            Self::SkippedModule(err) => write!(f, "Skipped module: {}", err),
+            Self::StrayFile { path } => write!(f, "Ignored stray file: {}", path.display()),
+            Self::ConflictingModule {
+                address,
+                package,
+                module,
+                kept,
+                ignored,
+            } => write!(
+                f,
+                "Conflicting module {}::{} at {}: using {}, ignoring {}",
+                package,
+                module,
+                address.to_hex_literal(),
+                kept.display(),
+                ignored.display()
+            ),
+            Self::SharedAddress {
+                address,
+                kept,
+                ignored,
+                path,
+            } => write!(
+                f,
+                "Packages {} and {} share address {}: ignoring {}",
+                kept,
+                ignored,
+                address.to_hex_literal(),
+                path.display()
+            ),
+            Self::DuplicatePackageName {
+                name,
+                kept,
+                ignored,
+            } => write!(
+                f,
+                "Packages at {} and {} are both named {}: {} is only reachable by address",
+                kept.to_hex_literal(),
+                ignored.to_hex_literal(),
+                name,
+                ignored.to_hex_literal()
+            ),
+        }
+    }
+}
+
+/// Parsed packages together with everything that was skipped to produce them.
+#[derive(Debug)]
+pub struct ParsedSummaries {
+    pub packages: move_model_2::summary::Packages,
+    pub metadata: Option<RootPackageMetadata>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Content hash of every file that was read, keyed by its path relative
+    /// to the parsed folder. An archive is a single file with an empty path.
+    pub file_hashes: BTreeMap<PathBuf, u64>,
+}
+
+/// Contents of root_package_metadata.json, written by `sui move summary`
+/// when summarizing a published package.
+#[derive(Debug, Clone, Default, serde::Serialize, Deserialize)]
+pub struct RootPackageMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_package_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_package_original_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_package_version: Option<u64>,
+    /// Any fields not known to this version of the explorer.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+impl RootPackageMetadata {
+    pub fn root_package_id(&self) -> Option<AccountAddress> {
+        self.root_package_id
+            .as_deref()
+            .and_then(|id| AccountAddress::from_hex_literal(id).ok())
+    }
+
+    pub fn root_package_original_id(&self) -> Option<AccountAddress> {
+        self.root_package_original_id
+            .as_deref()
+            .and_then(|id| AccountAddress::from_hex_literal(id).ok())
+    }
+}
+
 /// Parse summary files from a directory path and return the structures
 /// that match the output_summaries function signature.
 pub fn parse_summaries<P: Into<std::path::PathBuf>>(
     pb: P,
 ) -> Result<move_model_2::summary::Packages> {
-    let bf: std::path::PathBuf = pb.into();
-    let summaries_dir = bf.as_path();
-
-    // Validate directory exists
-    if !summaries_dir.exists() {
-        return Err(anyhow!(
-            "Summaries directory does not exist: {}",
-            summaries_dir.display()
-        ));
+    let parsed = parse_summaries_with(pb, ParseMode::Strict)?;
+    for diagnostic in &parsed.diagnostics {
+        eprintln!("{}", diagnostic);
     }
+    Ok(parsed.packages)
+}
 
-    if !summaries_dir.is_dir() {
-        return Err(anyhow!(
-            "Path is not a directory: {}",
-            summaries_dir.display()
-        ));
-    }
+/// Parse summary files from a directory path, returning the diagnostics
+/// collected along the way. In `ParseMode::Lenient` malformed packages and
+/// modules are skipped rather than failing the whole parse.
+///
+/// The path may also point at a `.zip`, `.tar` or `.tar.gz` archive that
+/// contains a summaries directory.
+pub fn parse_summaries_with<P: Into<std::path::PathBuf>>(
+    pb: P,
+    mode: ParseMode,
+) -> Result<ParsedSummaries> {
+    let bf: std::path::PathBuf = pb.into();
+
+    // Validate the directory or archive exists
+    let source = Source::open(&bf)?;
 
     // Parse address mapping
-    let address_mapping =
-        parse_address_mapping(summaries_dir).context("Failed to parse address mapping")?;
+    let address_mapping = parse_address_mapping(&source)?;
 
     // Parse additional metadata (optional)
-    let _additional_metadata = parse_metadata::<serde_json::Value>(summaries_dir)
-        .context("Failed to parse additional metadata")?;
+    let metadata = parse_metadata::<Option<RootPackageMetadata>>(&source)?.flatten();
 
     // Parse packages and modules
-    let packages =
-        parse_packages(summaries_dir, &address_mapping).context("Failed to parse packages")?;
+    let mut diagnostics = Vec::new();
+    let packages = parse_packages(&source, &address_mapping, mode, &mut diagnostics)?;
+
+    Ok(ParsedSummaries {
+        packages,
+        metadata,
+        diagnostics,
+        file_hashes: source.file_hashes(),
+    })
+}
+
+/// In lenient mode record `err` as a diagnostic, otherwise fail with it.
+fn skip_or_fail(
+    mode: ParseMode,
+    err: SummaryError,
+    diagnostic: fn(SummaryError) -> Diagnostic,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<()> {
+    match mode {
+        ParseMode::Strict => Err(err),
+        ParseMode::Lenient => {
+            diagnostics.push(diagnostic(err));
+            Ok(())
+        }
+    }
+}
 
-    Ok(packages)
+fn is_json_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == JSON_EXT)
 }
 
 /// Parse the address_mapping.json file
-fn parse_address_mapping(summaries_dir: &Path) -> Result<BTreeMap<Symbol, AccountAddress>> {
-    let mapping_file = summaries_dir
-        .join(ADDRESS_MAPPING_FILENAME)
-        .with_extension(JSON_EXT);
-
-    if !mapping_file.exists() {
-        return Err(anyhow!(
-            "Address mapping file not found: {}",
-            mapping_file.display()
-        ));
-    }
+fn parse_address_mapping(source: &Source) -> Result<BTreeMap<Symbol, AccountAddress>> {
+    let mapping_rel = Path::new(ADDRESS_MAPPING_FILENAME).with_extension(JSON_EXT);
+    let mapping_file = source.display_path(&mapping_rel);
 
-    let content = fs::read_to_string(&mapping_file).with_context(|| {
-        format!(
-            "Failed to read address mapping file: {}",
-            mapping_file.display()
-        )
-    })?;
+    let Some(content) = source.read_to_string(&mapping_rel)? else {
+        return Err(SummaryError::MissingAddressMapping { path: mapping_file });
+    };
 
     // The JSON contains String representations of addresses, we need to convert to AccountAddress
     let string_mapping: BTreeMap<Symbol, String> =
-        serde_json::from_str(&content).with_context(|| {
-            format!(
-                "Failed to parse address mapping JSON from: {}",
-                mapping_file.display()
-            )
-        })?;
+        serde_json::from_str(&content).map_err(SummaryError::invalid_json(&mapping_file))?;
 
     let mut address_mapping = BTreeMap::new();
     for (symbol, addr_str) in string_mapping {
-        let address = AccountAddress::from_hex_literal(&addr_str).with_context(|| {
-            format!(
-                "Failed to parse address '{}' for symbol '{}'",
-                addr_str, symbol
-            )
-        })?;
+        let Ok(address) = AccountAddress::from_hex_literal(&addr_str) else {
+            let (line, column) = locate_value(&content, &symbol, &addr_str);
+            return Err(SummaryError::InvalidAddress {
+                path: mapping_file,
+                symbol: symbol.to_string(),
+                address: addr_str,
+                line,
+                column,
+            });
+        };
         address_mapping.insert(symbol, address);
     }
 
     Ok(address_mapping)
 }
 
+/// Find the 1-based line and column of the string value `value` that follows
+/// the key `key` in a JSON object. Falls back to the start of the file.
+fn locate_value(content: &str, key: &str, value: &str) -> (usize, usize) {
+    let key_pat = format!("\"{}\"", key);
+    let value_pat = format!("\"{}\"", value);
+    let offset = content
+        .find(&key_pat)
+        .and_then(|key_at| {
+            let after_key = key_at + key_pat.len();
+            content[after_key..]
+                .find(&value_pat)
+                .map(|value_at| after_key + value_at)
+        })
+        .unwrap_or(0);
+
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
 /// Parse the optional root_package_metadata.json file
-fn parse_metadata<T: for<'de> Deserialize<'de>>(summaries_dir: &Path) -> Result<Option<T>> {
-    let metadata_file = summaries_dir
-        .join(METADATA_FILENAME)
-        .with_extension(JSON_EXT);
+fn parse_metadata<T: for<'de> Deserialize<'de>>(source: &Source) -> Result<Option<T>> {
+    let metadata_rel = Path::new(METADATA_FILENAME).with_extension(JSON_EXT);
 
-    if !metadata_file.exists() {
+    let Some(content) = source.read_to_string(&metadata_rel)? else {
         return Ok(None);
-    }
+    };
 
-    let content = fs::read_to_string(&metadata_file)
-        .with_context(|| format!("Failed to read metadata file: {}", metadata_file.display()))?;
-
-    let metadata: T = serde_json::from_str(&content).with_context(|| {
-        format!(
-            "Failed to parse metadata JSON from: {}",
-            metadata_file.display()
-        )
-    })?;
+    let metadata: T = serde_json::from_str(&content).map_err(SummaryError::invalid_json(
+        &source.display_path(&metadata_rel),
+    ))?;
 
     Ok(Some(metadata))
 }
 
 /// Parse all package directories and their module files
+///
+/// Packages are parsed in parallel, then assembled in directory order so
+/// that the output and the diagnostics stay deterministic.
 fn parse_packages(
-    summaries_dir: &Path,
+    source: &Source,
     address_mapping: &BTreeMap<Symbol, AccountAddress>,
+    mode: ParseMode,
+    diagnostics: &mut Vec<Diagnostic>,
 ) -> Result<move_model_2::summary::Packages> {
     let mut packages = BTreeMap::new();
 
     // Read all entries in the summaries directory
-    let entries = fs::read_dir(summaries_dir).with_context(|| {
-        format!(
-            "Failed to read summaries directory: {}",
-            summaries_dir.display()
-        )
-    })?;
-
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-
+    let mut package_dirs = Vec::new();
+    for entry in source.entries(Path::new(""))? {
         // Skip files (we only want package directories)
-        if !path.is_dir() {
+        if !entry.is_dir {
+            let is_known = [ADDRESS_MAPPING_FILENAME, METADATA_FILENAME]
+                .iter()
+                .any(|name| entry.path == Path::new(name).with_extension(JSON_EXT));
+            if mode == ParseMode::Lenient && !is_known {
+                diagnostics.push(Diagnostic::StrayFile {
+                    path: source.display_path(&entry.path),
+                });
+            }
             continue;
         }
+        package_dirs.push(entry.path);
+    }
 
-        let package_name = path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .context("Invalid package directory name")?;
-
-        let package_symbol = Symbol::from(package_name);
+    let parsed: Vec<_> = package_dirs
+        .into_par_iter()
+        .map(|rel| {
+            let mut package_diagnostics = Vec::new();
+            let package = parse_package_dir(
+                source,
+                &rel,
+                address_mapping,
+                mode,
+                &mut package_diagnostics,
+            );
+            (package, package_diagnostics)
+        })
+        .collect();
+
+    for (package, package_diagnostics) in parsed {
+        diagnostics.extend(package_diagnostics);
+        if let Some((package_address, package)) = package? {
+            packages.insert(package_address, package);
+        }
+    }
 
-        let pkg_read = address_mapping.get(&package_symbol);
+    Ok(move_model_2::summary::Packages { packages })
+}
 
-        let Some(package_address) = pkg_read else {
-            println!(
-                "Ignoring package '{}' - symbol not found in address_mapping.json",
-                package_symbol
-            );
-            continue;
+/// Parse one package directory, returning `None` if it was skipped
+fn parse_package_dir(
+    source: &Source,
+    rel: &Path,
+    address_mapping: &BTreeMap<Symbol, AccountAddress>,
+    mode: ParseMode,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Option<(AccountAddress, move_model_2::summary::Package)>> {
+    let Some(package_name) = rel.file_name().and_then(|name| name.to_str()) else {
+        let err = SummaryError::InvalidFileName {
+            path: source.display_path(rel),
         };
+        skip_or_fail(mode, err, Diagnostic::SkippedPackage, diagnostics)?;
+        return Ok(None);
+    };
 
-        // Parse all modules in this package directory
-        let modules = parse_modules_in_package(&path)
-            .with_context(|| format!("Failed to parse modules in package: {}", package_name))?;
+    let package_symbol = Symbol::from(package_name);
 
-        let package = move_model_2::summary::Package {
-            name: Some(Symbol::from(package_name)),
-            modules,
+    let pkg_read = address_mapping.get(&package_symbol);
+
+    let Some(package_address) = pkg_read else {
+        let err = SummaryError::UnknownPackage {
+            package: package_name.to_string(),
+            path: source.display_path(rel),
         };
+        skip_or_fail(mode, err, Diagnostic::SkippedPackage, diagnostics)?;
+        return Ok(None);
+    };
+
+    // Parse all modules in this package directory
+    let modules = match parse_modules_in_package(source, rel, mode, diagnostics) {
+        Ok(modules) => modules,
+        Err(err) => {
+            skip_or_fail(mode, err, Diagnostic::SkippedPackage, diagnostics)?;
+            return Ok(None);
+        }
+    };
 
-        packages.insert(*package_address, package);
-    }
+    let package = move_model_2::summary::Package {
+        name: Some(package_symbol),
+        modules,
+    };
 
-    Ok(move_model_2::summary::Packages { packages })
+    Ok(Some((*package_address, package)))
 }
 
-/// Parse all module JSON files in a package directory
+/// Parse all module JSON files in a package directory, in parallel
 fn parse_modules_in_package(
+    source: &Source,
     package_dir: &Path,
+    mode: ParseMode,
+    diagnostics: &mut Vec<Diagnostic>,
 ) -> Result<BTreeMap<Symbol, move_model_2::summary::Module>> {
     let mut modules = BTreeMap::new();
 
-    let entries = fs::read_dir(package_dir).with_context(|| {
-        format!(
-            "Failed to read package directory: {}",
-            package_dir.display()
-        )
-    })?;
+    let mut module_files = Vec::new();
+    for entry in source.entries(package_dir)? {
+        if mode == ParseMode::Lenient && (entry.is_dir || !is_json_file(&entry.path)) {
+            diagnostics.push(Diagnostic::StrayFile {
+                path: source.display_path(&entry.path),
+            });
+            continue;
+        }
+        module_files.push(entry.path);
+    }
 
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
+    let parsed: Vec<_> = module_files
+        .par_iter()
+        .map(|rel| parse_module_file(source, rel))
+        .collect();
+
+    for module in parsed {
+        match module {
+            Ok((module_name, module)) => {
+                modules.insert(module_name, module);
+            }
+            Err(err) => skip_or_fail(mode, err, Diagnostic::SkippedModule, diagnostics)?,
+        }
+    }
 
-        let module_name = path
-            .file_stem()
-            .and_then(|name| name.to_str())
-            .context("Invalid module file name")?;
+    Ok(modules)
+}
 
-        let content = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read module file: {}", path.display()))?;
+/// Parse a single module JSON file, keyed by its file stem
+fn parse_module_file(
+    source: &Source,
+    rel: &Path,
+) -> Result<(Symbol, move_model_2::summary::Module)> {
+    let path = source.display_path(rel);
+    let Some(module_name) = rel.file_stem().and_then(|name| name.to_str()) else {
+        return Err(SummaryError::InvalidFileName { path });
+    };
+    let module_name = Symbol::from(module_name);
+
+    let Some(content) = source.read_to_string(rel)? else {
+        return Err(SummaryError::Unreadable {
+            path,
+            source: std::io::ErrorKind::NotFound.into(),
+        });
+    };
+
+    let module = parse_module_json(path, &content)?;
+    Ok((module_name, module))
+}
 
-        let module: move_model_2::summary::Module = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse module JSON from: {}", path.display()))?;
+/// Parse the JSON of a module in any supported schema version.
+fn parse_module_json(path: PathBuf, content: &str) -> Result<move_model_2::summary::Module> {
+    let value: Value = serde_json::from_str(content).map_err(SummaryError::invalid_json(&path))?;
+    let schema_error = |source: serde_json::Error| SummaryError::ModuleSchema {
+        line: source.line(),
+        column: source.column(),
+        path: path.clone(),
+        source,
+    };
+    match SchemaVersion::detect(&value) {
+        // Deserialize the text again so schema errors keep their location
+        Ok(SchemaVersion::CURRENT) => serde_json::from_str(content).map_err(|source| {
+            // Keys the current layout does not know point at a newer
+            // release rather than a broken file
+            if has_unknown_keys(&value) {
+                SummaryError::UnsupportedSchema {
+                    path: path.clone(),
+                    version: None,
+                }
+            } else {
+                schema_error(source)
+            }
+        }),
+        Ok(version) => serde_json::from_value(version.upgrade(value)).map_err(schema_error),
+        Err(version) => Err(SummaryError::UnsupportedSchema {
+            path,
+            version: Some(version),
+        }),
+    }
+}
 
-        modules.insert(Symbol::from(module_name), module);
+/// Layouts of module JSON written by different `sui move summary` releases.
+///
+/// Files may state their layout in a top-level `schema_version` field,
+/// otherwise it is inferred from the shape of the JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaVersion {
+    /// Keys spelled without the trailing underscore of the Rust field names
+    /// (`type`, `return`, `macro`), datatype type parameters with `name` and
+    /// `constraints` inline, and `Package` visibility.
+    V0,
+    /// The layout of the pinned `move-model-2`.
+    V1,
+}
+
+impl SchemaVersion {
+    pub const CURRENT: Self = Self::V1;
+
+    /// The layout of a module, or the stated version if it is not supported.
+    pub fn detect(module: &Value) -> std::result::Result<Self, u64> {
+        if let Some(version) = module.get(SCHEMA_VERSION_KEY).and_then(Value::as_u64) {
+            return match version {
+                0 => Ok(Self::V0),
+                1 => Ok(Self::V1),
+                _ => Err(version),
+            };
+        }
+        if is_v0(module) {
+            Ok(Self::V0)
+        } else {
+            Ok(Self::V1)
+        }
     }
 
-    Ok(modules)
+    /// Rewrite a module of this layout into the current one.
+    pub fn upgrade(self, mut module: Value) -> Value {
+        if let Some(object) = module.as_object_mut() {
+            object.remove(SCHEMA_VERSION_KEY);
+        }
+        if self == Self::V0 {
+            upgrade_v0(&mut module);
+        }
+        module
+    }
+}
+
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Keys of the objects of the current layout, used to tell a newer layout
+/// from a malformed file.
+const MODULE_KEYS: [&str; 8] = [
+    SCHEMA_VERSION_KEY,
+    "id",
+    "doc",
+    "attributes",
+    "immediate_dependencies",
+    "functions",
+    "structs",
+    "enums",
+];
+const FUNCTION_KEYS: [&str; 9] = [
+    "index",
+    "doc",
+    "attributes",
+    "visibility",
+    "entry",
+    "macro_",
+    "type_parameters",
+    "parameters",
+    "return_",
+];
+const PARAMETER_KEYS: [&str; 2] = ["name", "type_"];
+const DATATYPE_KEYS: [&str; 7] = [
+    "index",
+    "doc",
+    "attributes",
+    "abilities",
+    "type_parameters",
+    "fields",
+    "variants",
+];
+const VARIANT_KEYS: [&str; 3] = ["index", "doc", "fields"];
+const FIELD_KEYS: [&str; 3] = ["index", "doc", "type_"];
+
+fn definitions<'a>(module: &'a Value, kind: &str) -> impl Iterator<Item = &'a Value> {
+    module
+        .get(kind)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|definitions| definitions.values())
+}
+
+/// Field objects of a `Fields` object, not its name-keyed map.
+fn fields_mut(fields: &mut Value) -> impl Iterator<Item = &mut Value> {
+    fields
+        .get_mut("fields")
+        .and_then(Value::as_object_mut)
+        .into_iter()
+        .flat_map(|fields| fields.values_mut())
+}
+
+fn has_unknown_keys(module: &Value) -> bool {
+    let unknown = |value: &Value, known: &[&str]| {
+        value
+            .as_object()
+            .is_some_and(|object| object.keys().any(|key| !known.contains(&key.as_str())))
+    };
+    let fields_unknown = |fields: Option<&Value>| {
+        fields
+            .and_then(|fields| fields.get("fields"))
+            .and_then(Value::as_object)
+            .is_some_and(|fields| fields.values().any(|field| unknown(field, &FIELD_KEYS)))
+    };
+    let function_unknown = |function: &Value| {
+        unknown(function, &FUNCTION_KEYS)
+            || function
+                .get("parameters")
+                .and_then(Value::as_array)
+                .is_some_and(|params| params.iter().any(|param| unknown(param, &PARAMETER_KEYS)))
+    };
+    let datatype_unknown = |datatype: &Value| {
+        unknown(datatype, &DATATYPE_KEYS)
+            || fields_unknown(datatype.get("fields"))
+            || definitions(datatype, "variants").any(|variant| {
+                unknown(variant, &VARIANT_KEYS) || fields_unknown(variant.get("fields"))
+            })
+    };
+    unknown(module, &MODULE_KEYS)
+        || definitions(module, "functions").any(function_unknown)
+        || definitions(module, "structs").any(datatype_unknown)
+        || definitions(module, "enums").any(datatype_unknown)
+}
+
+fn is_v0(module: &Value) -> bool {
+    let function_v0 = |function: &Value| {
+        function.get("return").is_some()
+            || function
+                .get("parameters")
+                .and_then(Value::as_array)
+                .is_some_and(|params| params.iter().any(|param| param.get("type").is_some()))
+    };
+    let tparams_v0 = |datatype: &Value| {
+        datatype
+            .get("type_parameters")
+            .and_then(Value::as_array)
+            .is_some_and(|tparams| tparams.iter().any(|tparam| tparam.get("tparam").is_none()))
+    };
+    definitions(module, "functions").any(function_v0)
+        || definitions(module, "structs").any(tparams_v0)
+        || definitions(module, "enums").any(tparams_v0)
+}
+
+fn upgrade_v0(module: &mut Value) {
+    for kind in ["functions", "structs", "enums"] {
+        let Some(definitions) = module.get_mut(kind).and_then(Value::as_object_mut) else {
+            continue;
+        };
+        for definition in definitions.values_mut() {
+            match kind {
+                "functions" => upgrade_v0_function(definition),
+                "structs" => {
+                    if let Some(fields) = definition.get_mut("fields") {
+                        fields_mut(fields).for_each(|field| rename_key(field, "type", "type_"));
+                    }
+                }
+                _ => {
+                    let variants = definition
+                        .get_mut("variants")
+                        .and_then(Value::as_object_mut)
+                        .into_iter()
+                        .flat_map(|variants| variants.values_mut());
+                    for variant in variants {
+                        if let Some(fields) = variant.get_mut("fields") {
+                            fields_mut(fields).for_each(|field| rename_key(field, "type", "type_"));
+                        }
+                    }
+                }
+            }
+            if kind != "functions"
+                && let Some(tparams) = definition
+                    .get_mut("type_parameters")
+                    .and_then(Value::as_array_mut)
+            {
+                for tparam in tparams {
+                    nest_datatype_tparam(tparam);
+                }
+            }
+        }
+    }
+}
+
+/// Rename the V0 keys of a function and its parameters. Keys of the
+/// name-keyed maps are never touched, so a function or field called `type`
+/// keeps its name.
+fn upgrade_v0_function(function: &mut Value) {
+    rename_key(function, "return", "return_");
+    rename_key(function, "macro", "macro_");
+    if let Some(visibility) = function.get_mut("visibility")
+        && visibility == "Package"
+    {
+        *visibility = Value::from("Friend");
+    }
+    let params = function
+        .get_mut("parameters")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten();
+    for param in params {
+        rename_key(param, "type", "type_");
+    }
+}
+
+fn rename_key(object: &mut Value, old: &str, new: &str) {
+    let Some(object) = object.as_object_mut() else {
+        return;
+    };
+    if !object.contains_key(new)
+        && let Some(value) = object.remove(old)
+    {
+        object.insert(new.to_string(), value);
+    }
+}
+
+/// `{"phantom", "name", "constraints"}` to `{"phantom", "tparam": {"name", "constraints"}}`
+fn nest_datatype_tparam(tparam: &mut Value) {
+    let Some(object) = tparam.as_object_mut() else {
+        return;
+    };
+    if object.contains_key("tparam") {
+        return;
+    }
+    let mut inner = serde_json::Map::new();
+    for key in ["name", "constraints"] {
+        if let Some(value) = object.remove(key) {
+            inner.insert(key.to_string(), value);
+        }
+    }
+    object.insert("tparam".to_string(), Value::Object(inner));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use move_model_2::summary::{Ability, Type, Visibility};
+    use std::fs;
+
+    fn parse(name: &str, content: &str) -> Result<move_model_2::summary::Module> {
+        parse_module_json(PathBuf::from(name), content)
+    }
+
+    #[test]
+    fn parses_v0_module() {
+        let v0 = include_str!("../tests/fixtures/schema/v0.json");
+        assert_eq!(
+            SchemaVersion::detect(&serde_json::from_str(v0).unwrap()),
+            Ok(SchemaVersion::V0)
+        );
+        let module = parse("v0.json", v0).unwrap();
+
+        // Definitions and fields named like V0 keys keep their names
+        let function = &module.functions[&Symbol::from("type")];
+        assert!(matches!(function.visibility, Visibility::Friend));
+        assert_eq!(function.macro_, Some(false));
+        assert!(matches!(function.return_[..], [Type::U8]));
+        assert!(matches!(
+            function.parameters[0].type_,
+            Type::Reference(false, _)
+        ));
+        let pool = &module.structs[&Symbol::from("Pool")];
+        let fields = &pool.fields.fields;
+        assert!(matches!(fields[&Symbol::from("type")].type_, Type::U8));
+        assert!(matches!(fields[&Symbol::from("return")].type_, Type::U64));
+        assert!(pool.type_parameters[0].phantom);
+        assert_eq!(pool.type_parameters[0].tparam.name, Some(Symbol::from("T")));
+        let variant = &module.enums[&Symbol::from("Kind")].variants[&Symbol::from("Fixed")];
+        assert!(matches!(
+            variant.fields.fields[&Symbol::from("0")].type_,
+            Type::U64
+        ));
+    }
+
+    #[test]
+    fn parses_v1_module() {
+        let module = parse("v1.json", include_str!("../tests/fixtures/schema/v1.json")).unwrap();
+        assert_eq!(module.id.name, Symbol::from("pool"));
+        assert!(
+            module.structs[&Symbol::from("Pool")]
+                .abilities
+                .0
+                .contains(&Ability::Key)
+        );
+        assert!(matches!(
+            module.functions[&Symbol::from("type")].return_[..],
+            [Type::U8]
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let err = parse(
+            "unsupported.json",
+            include_str!("../tests/fixtures/schema/unsupported.json"),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            SummaryError::UnsupportedSchema {
+                version: Some(7),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_layout() {
+        let mut module: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/schema/v1.json")).unwrap();
+        let object = module.as_object_mut().unwrap();
+        object.remove(SCHEMA_VERSION_KEY);
+        object.insert("constants".to_string(), Value::Object(Default::default()));
+        object.remove("enums");
+        let err = parse("unknown.json", &module.to_string()).unwrap_err();
+        assert!(matches!(
+            err,
+            SummaryError::UnsupportedSchema { version: None, .. }
+        ));
+    }
+
+    #[test]
+    fn reports_malformed_current_module() {
+        let mut module: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/schema/v1.json")).unwrap();
+        module.as_object_mut().unwrap().remove("enums");
+        let err = parse("malformed.json", &module.to_string()).unwrap_err();
+        assert!(matches!(err, SummaryError::ModuleSchema { .. }));
+    }
+
+    /// A summaries folder with a `pools` package holding the fixture module
+    /// and a malformed one, and a package folder missing from the mapping.
+    fn malformed_summaries(dir: &TempDir) -> (PathBuf, PathBuf) {
+        let root = dir.path();
+        fs::write(root.join("address_mapping.json"), r#"{"pools": "0x2"}"#).unwrap();
+        fs::create_dir_all(root.join("pools")).unwrap();
+        fs::create_dir_all(root.join("stray")).unwrap();
+        let fixture = include_str!("../tests/fixtures/schema/v1.json");
+        fs::write(root.join("pools").join("pool.json"), fixture).unwrap();
+        fs::write(root.join("pools").join("broken.json"), "{\n  \"id\"").unwrap();
+        fs::write(root.join("stray").join("pool.json"), fixture).unwrap();
+        (root.join("pools").join("broken.json"), root.join("stray"))
+    }
+
+    #[test]
+    fn lenient_mode_skips_malformed_modules_and_unknown_packages() {
+        let dir = TempDir::new("parser-lenient");
+        let (broken, stray) = malformed_summaries(&dir);
+
+        let parsed = parse_summaries_with(dir.path(), ParseMode::Lenient).unwrap();
+        let packages = &parsed.packages.packages;
+        assert_eq!(packages.len(), 1);
+        let pools = &packages[&AccountAddress::from_hex_literal("0x2").unwrap()];
+        assert_eq!(pools.name, Some(Symbol::from("pools")));
+        assert_eq!(
+            pools.modules.keys().collect::<Vec<_>>(),
+            [&Symbol::from("pool")]
+        );
+        assert!(matches!(
+            parsed.diagnostics.as_slice(),
+            [
+                Diagnostic::SkippedModule(SummaryError::InvalidJson { path: module, .. }),
+                Diagnostic::SkippedPackage(SummaryError::UnknownPackage { path: package, .. }),
+            ] if *module == broken && *package == stray
+        ));
+    }
+
+    #[test]
+    fn strict_mode_fails_on_malformed_modules_and_unknown_packages() {
+        let dir = TempDir::new("parser-strict");
+        let (broken, stray) = malformed_summaries(&dir);
+
+        let err = parse_summaries_with(dir.path(), ParseMode::Strict).unwrap_err();
+        assert!(matches!(&err, SummaryError::InvalidJson { path, .. } if *path == broken));
+
+        fs::remove_file(&broken).unwrap();
+        let err = parse_summaries_with(dir.path(), ParseMode::Strict).unwrap_err();
+        assert!(matches!(&err, SummaryError::UnknownPackage { path, .. } if *path == stray));
+    }
 }
@@ -0,0 +1,116 @@
+use crate::{LoadOptions, PackageTree};
+use notify::{RecursiveMode, Watcher};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{mpsc, watch};
+
+/// How long to let a burst of file system events settle before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A `PackageTree` shared between sessions that can be swapped for a newer
+/// snapshot while it is being served. Readers always see a complete tree.
+#[derive(Clone)]
+pub struct LivePackageTree {
+    sender: Arc<watch::Sender<PackageTree>>,
+}
+
+impl LivePackageTree {
+    pub fn new(tree: PackageTree) -> Self {
+        let (sender, _) = watch::channel(tree);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// The latest snapshot.
+    pub fn current(&self) -> PackageTree {
+        self.sender.borrow().clone()
+    }
+
+    pub fn replace(&self, tree: PackageTree) {
+        self.sender.send_replace(tree);
+    }
+
+    /// Receive a notification every time the tree is replaced.
+    pub fn subscribe(&self) -> watch::Receiver<PackageTree> {
+        self.sender.subscribe()
+    }
+}
+
+impl From<PackageTree> for LivePackageTree {
+    fn from(tree: PackageTree) -> Self {
+        Self::new(tree)
+    }
+}
+
+/// Watch `paths` recursively and send on `tx` once per burst of changes below them.
+/// Watching stops when the returned watcher is dropped.
+pub fn watch_paths<P: AsRef<Path>>(
+    paths: &[P],
+    tx: mpsc::UnboundedSender<()>,
+) -> notify::Result<notify::RecommendedWatcher> {
+    let (events_tx, events_rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if res.is_ok_and(|event| !event.kind.is_access()) {
+            let _ = events_tx.send(());
+        }
+    })?;
+    for path in paths {
+        watcher.watch(path.as_ref(), RecursiveMode::Recursive)?;
+    }
+
+    // `sui move summary` rewrites many files at once, so wait for the events
+    // to settle before reporting a change. The thread exits with the watcher.
+    std::thread::spawn(move || {
+        while events_rx.recv().is_ok() {
+            while events_rx.recv_timeout(DEBOUNCE).is_ok() {}
+            if tx.send(()).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(watcher)
+}
+
+/// Reparse `paths` whenever one of them changes and swap the result into `live`.
+/// A failed reparse keeps the previous snapshot.
+///
+/// Must be called from within a tokio runtime. Reloading stops when the
+/// returned watcher is dropped.
+pub fn spawn_reloader(
+    paths: Vec<PathBuf>,
+    options: LoadOptions,
+    live: LivePackageTree,
+) -> notify::Result<notify::RecommendedWatcher> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let watcher = watch_paths(&paths, tx)?;
+
+    tokio::spawn(async move {
+        while rx.recv().await.is_some() {
+            let paths = paths.clone();
+            let reparsed =
+                tokio::task::spawn_blocking(move || PackageTree::load_many(&paths, options)).await;
+            match reparsed {
+                Ok(Ok(tree)) => {
+                    eprintln!("reloaded summaries");
+                    for diagnostic in tree.diagnostics() {
+                        eprintln!("{}", diagnostic);
+                    }
+                    live.replace(tree);
+                }
+                Ok(Err(err)) => {
+                    eprintln!("reload failed, keeping previous summaries: {}", err);
+                }
+                Err(err) => {
+                    eprintln!("reload task failed: {}", err);
+                }
+            }
+        }
+    });
+
+    Ok(watcher)
+}
@@ -0,0 +1,686 @@
+//! Move source syntax for summary definitions.
+//!
+//! Definitions are rendered as declarations without bodies, e.g.
+//!
+//! ```text
+//! /// Split `amount` off `self`.
+//! public fun split<T>(self: &mut Coin<T>, amount: u64, ctx: &mut TxContext): Coin<T>
+//! ```
+//!
+//! Datatypes are named without their module, and unnamed parameters and type
+//! parameters of modules loaded from bytecode are spelled `_0` and `T0`.
+
+use crate::ModuleKey;
+use move_model_2::summary::{
+    AbilitySet, Attribute, Enum, Fields, Function, Module, Struct, Type, Visibility,
+};
+use move_symbol_pool::Symbol;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Function signatures longer than this get one parameter per line.
+const MAX_LINE: usize = 100;
+
+const INDENT: &str = "    ";
+
+pub fn type_to_string(t: &Type) -> String {
+    match t {
+        Type::Bool => "bool".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::U128 => "u128".to_string(),
+        Type::U256 => "u256".to_string(),
+        Type::Address => "address".to_string(),
+        Type::Signer => "signer".to_string(),
+        Type::Datatype(dt) => {
+            let args: Vec<String> = dt
+                .type_arguments
+                .iter()
+                .map(|arg| type_to_string(&arg.argument))
+                .collect();
+            let args_str = if args.is_empty() {
+                "".to_string()
+            } else {
+                format!("<{}>", args.join(", "))
+            };
+            format!("{}{}", dt.name, args_str)
+        }
+        Type::Vector(inner) => format!("vector<{}>", type_to_string(inner)),
+        Type::Reference(is_mut, inner) => {
+            let mut_str = if *is_mut { "mut " } else { "" };
+            format!("&{}{}", mut_str, type_to_string(inner))
+        }
+        Type::TypeParameter(idx) => format!("T{}", idx),
+        Type::NamedTypeParameter(sym) => sym.to_string(),
+        Type::Tuple(types) => {
+            let type_strs: Vec<String> = types.iter().map(type_to_string).collect();
+            format!("({})", type_strs.join(", "))
+        }
+        Type::Fun(args, ret) => {
+            let arg_strs: Vec<String> = args.iter().map(type_to_string).collect();
+            format!("|{}| -> {}", arg_strs.join(", "), type_to_string(ret))
+        }
+        Type::Any => "_".to_string(),
+    }
+}
+
+/// One-line declaration of a function, without doc comment or attributes.
+pub fn function_signature(name: Symbol, function: &Function) -> String {
+    let (head, parameters, tail) = function_parts(name, function);
+    format!("{}({}){}", head, parameters.join(", "), tail)
+}
+
+/// One-line declaration of a struct, without doc comments or attributes.
+pub fn struct_signature(name: Symbol, struct_: &Struct) -> String {
+    let head = format!(
+        "public struct {}{}",
+        name,
+        datatype_tparams(
+            struct_
+                .type_parameters
+                .iter()
+                .map(|t| (t.phantom, &t.tparam))
+        )
+    );
+    let abilities = abilities_clause(&struct_.abilities);
+    if struct_.fields.fields.is_empty() {
+        format!("{}{} {{}}", head, abilities)
+    } else if struct_.fields.positional_fields {
+        format!("{}{}{};", head, inline_fields(&struct_.fields), abilities)
+    } else {
+        format!("{}{}{}", head, abilities, inline_fields(&struct_.fields))
+    }
+}
+
+/// One-line declaration of an enum, without doc comments or attributes.
+pub fn enum_signature(name: Symbol, enum_: &Enum) -> String {
+    let variants: Vec<String> = sorted_variants(enum_)
+        .into_iter()
+        .map(|(name, variant)| format!("{}{}", name, inline_fields(&variant.fields)))
+        .collect();
+    format!(
+        "public enum {}{}{} {{ {} }}",
+        name,
+        datatype_tparams(enum_.type_parameters.iter().map(|t| (t.phantom, &t.tparam))),
+        abilities_clause(&enum_.abilities),
+        variants.join(", ")
+    )
+}
+
+/// Doc comment, attributes and declaration of a function. Long parameter
+/// lists are split over several lines.
+pub fn render_function(name: Symbol, function: &Function) -> String {
+    let mut out = String::new();
+    push_doc(&mut out, function.doc.as_deref(), "");
+    push_attributes(&mut out, &function.attributes, "");
+
+    let signature = function_signature(name, function);
+    if signature.len() <= MAX_LINE {
+        out.push_str(&signature);
+        return out;
+    }
+    let (head, parameters, tail) = function_parts(name, function);
+    out.push_str(&head);
+    out.push_str("(\n");
+    for parameter in parameters {
+        out.push_str(&format!("{}{},\n", INDENT, parameter));
+    }
+    out.push(')');
+    out.push_str(&tail);
+    out
+}
+
+/// Doc comments, attributes and declaration of a struct, one field per line.
+pub fn render_struct(name: Symbol, struct_: &Struct) -> String {
+    let mut out = String::new();
+    push_doc(&mut out, struct_.doc.as_deref(), "");
+    push_attributes(&mut out, &struct_.attributes, "");
+    out.push_str(&format!(
+        "public struct {}{}",
+        name,
+        datatype_tparams(
+            struct_
+                .type_parameters
+                .iter()
+                .map(|t| (t.phantom, &t.tparam))
+        )
+    ));
+
+    let abilities = abilities_clause(&struct_.abilities);
+    if struct_.fields.fields.is_empty() {
+        out.push_str(&format!("{} {{}}", abilities));
+        return out;
+    }
+    if struct_.fields.positional_fields {
+        out.push_str(&format!("{}{};", inline_fields(&struct_.fields), abilities));
+        return out;
+    }
+    out.push_str(&abilities);
+    out.push_str(" {\n");
+    push_fields(&mut out, &struct_.fields, INDENT);
+    out.push('}');
+    out
+}
+
+/// Doc comments, attributes and declaration of an enum, one variant per
+/// line and one field per line for variants with named fields.
+pub fn render_enum(name: Symbol, enum_: &Enum) -> String {
+    let mut out = String::new();
+    push_doc(&mut out, enum_.doc.as_deref(), "");
+    push_attributes(&mut out, &enum_.attributes, "");
+    out.push_str(&format!(
+        "public enum {}{}{} {{\n",
+        name,
+        datatype_tparams(enum_.type_parameters.iter().map(|t| (t.phantom, &t.tparam))),
+        abilities_clause(&enum_.abilities)
+    ));
+    for (variant_name, variant) in sorted_variants(enum_) {
+        push_doc(&mut out, variant.doc.as_deref(), INDENT);
+        out.push_str(INDENT);
+        out.push_str(variant_name.as_str());
+        if variant.fields.positional_fields || variant.fields.fields.is_empty() {
+            out.push_str(&inline_fields(&variant.fields));
+        } else {
+            let indent = format!("{}{}", INDENT, INDENT);
+            out.push_str(" {\n");
+            push_fields(&mut out, &variant.fields, &indent);
+            out.push_str(INDENT);
+            out.push('}');
+        }
+        out.push_str(",\n");
+    }
+    out.push('}');
+    out
+}
+
+/// Interface of a whole module as a `.move` file: the module declaration,
+/// `use` lines for its immediate dependencies, then every struct, enum and
+/// function in declaration order, with doc comments and attributes.
+///
+/// `module_path` spells a module as `package::module`. Datatypes of other
+/// modules are imported by name so that the unqualified names in signatures
+/// resolve.
+pub fn render_module_interface(
+    module: &Module,
+    module_path: impl Fn(&ModuleKey) -> String,
+) -> String {
+    let key = (module.id.address, module.id.name);
+    let mut out = String::new();
+    push_doc(&mut out, module.doc.as_deref(), "");
+    push_attributes(&mut out, &module.attributes, "");
+    out.push_str(&format!("module {};\n", module_path(&key)));
+
+    let mut imports: BTreeMap<ModuleKey, BTreeSet<Symbol>> = module
+        .immediate_dependencies
+        .iter()
+        .map(|dependency| ((dependency.address, dependency.name), BTreeSet::new()))
+        .collect();
+    for type_ in module_types(module) {
+        for (address, module_name, name) in crate::usages::datatypes_in(type_) {
+            if (address, module_name) != key {
+                imports
+                    .entry((address, module_name))
+                    .or_default()
+                    .insert(name);
+            }
+        }
+    }
+    let mut uses: Vec<String> = imports
+        .iter()
+        .map(|(dependency, names)| {
+            let path = module_path(dependency);
+            match names.len() {
+                0 => format!("use {};", path),
+                1 => format!("use {}::{};", path, names.first().expect("one name")),
+                _ => {
+                    let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+                    format!("use {}::{{{}}};", path, names.join(", "))
+                }
+            }
+        })
+        .collect();
+    uses.sort();
+    if !uses.is_empty() {
+        out.push('\n');
+        for line in uses {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+
+    let mut structs: Vec<_> = module.structs.iter().collect();
+    structs.sort_by_key(|(_, struct_)| struct_.index);
+    let mut enums: Vec<_> = module.enums.iter().collect();
+    enums.sort_by_key(|(_, enum_)| enum_.index);
+    let mut functions: Vec<_> = module.functions.iter().collect();
+    functions.sort_by_key(|(_, function)| function.index);
+
+    let declarations = structs
+        .into_iter()
+        .map(|(name, struct_)| render_struct(*name, struct_))
+        .chain(
+            enums
+                .into_iter()
+                .map(|(name, enum_)| render_enum(*name, enum_)),
+        )
+        .chain(
+            functions
+                .into_iter()
+                .map(|(name, function)| format!("{};", render_function(*name, function))),
+        );
+    for declaration in declarations {
+        out.push('\n');
+        out.push_str(&declaration);
+        out.push('\n');
+    }
+    out
+}
+
+/// Every type in the signatures and fields of a module.
+fn module_types(module: &Module) -> impl Iterator<Item = &Type> {
+    let functions = module.functions.values().flat_map(|function| {
+        function
+            .parameters
+            .iter()
+            .map(|param| &param.type_)
+            .chain(&function.return_)
+    });
+    let struct_fields = module
+        .structs
+        .values()
+        .flat_map(|struct_| struct_.fields.fields.values());
+    let variant_fields = module
+        .enums
+        .values()
+        .flat_map(|enum_| enum_.variants.values())
+        .flat_map(|variant| variant.fields.fields.values());
+    functions.chain(
+        struct_fields
+            .chain(variant_fields)
+            .map(|field| &field.type_),
+    )
+}
+
+/// `public entry fun name<T: copy>`, the parameters and `: Ret`.
+fn function_parts(name: Symbol, function: &Function) -> (String, Vec<String>, String) {
+    let visibility = match function.visibility {
+        Visibility::Public => "public ",
+        Visibility::Friend => "public(package) ",
+        _ => "",
+    };
+    let entry = if function.entry { "entry " } else { "" };
+    let macro_ = if function.macro_ == Some(true) {
+        "macro "
+    } else {
+        ""
+    };
+    let tparams: Vec<String> = function
+        .type_parameters
+        .iter()
+        .enumerate()
+        .map(|(i, tparam)| {
+            let name = tparam_name(i, tparam.name);
+            // Macro type parameters are spelled `$T`
+            let name = if macro_.is_empty() || name.starts_with('$') {
+                name
+            } else {
+                format!("${}", name)
+            };
+            format!("{}{}", name, constraints(&tparam.constraints))
+        })
+        .collect();
+    let tparams = if tparams.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", tparams.join(", "))
+    };
+    let head = format!("{}{}{}fun {}{}", visibility, entry, macro_, name, tparams);
+
+    let parameters = function
+        .parameters
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let name = param
+                .name
+                .map_or_else(|| format!("_{}", i), |name| name.to_string());
+            format!("{}: {}", name, type_to_string(&param.type_))
+        })
+        .collect();
+
+    let tail = match function.return_.as_slice() {
+        [] => String::new(),
+        [type_] => format!(": {}", type_to_string(type_)),
+        types => {
+            let types: Vec<String> = types.iter().map(type_to_string).collect();
+            format!(": ({})", types.join(", "))
+        }
+    };
+    (head, parameters, tail)
+}
+
+/// Type parameters loaded from bytecode are unnamed, use the `T{idx}`
+/// spelling of `type_to_string` for them.
+fn tparam_name(i: usize, name: Option<Symbol>) -> String {
+    name.map_or_else(|| format!("T{}", i), |name| name.to_string())
+}
+
+/// `<phantom T: store, U>` for a struct or enum.
+fn datatype_tparams<'a>(
+    tparams: impl Iterator<Item = (bool, &'a move_model_2::summary::TParam)>,
+) -> String {
+    let tparams: Vec<String> = tparams
+        .enumerate()
+        .map(|(i, (phantom, tparam))| {
+            format!(
+                "{}{}{}",
+                if phantom { "phantom " } else { "" },
+                tparam_name(i, tparam.name),
+                constraints(&tparam.constraints)
+            )
+        })
+        .collect();
+    if tparams.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", tparams.join(", "))
+    }
+}
+
+fn ability_names(abilities: &AbilitySet) -> Vec<&'static str> {
+    abilities
+        .0
+        .iter()
+        .map(|ability| match ability {
+            move_model_2::summary::Ability::Copy => "copy",
+            move_model_2::summary::Ability::Drop => "drop",
+            move_model_2::summary::Ability::Store => "store",
+            move_model_2::summary::Ability::Key => "key",
+        })
+        .collect()
+}
+
+/// `: copy + drop`, or nothing without constraints.
+fn constraints(abilities: &AbilitySet) -> String {
+    let names = ability_names(abilities);
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", names.join(" + "))
+    }
+}
+
+/// ` has key, store`, or nothing without abilities.
+fn abilities_clause(abilities: &AbilitySet) -> String {
+    let names = ability_names(abilities);
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(" has {}", names.join(", "))
+    }
+}
+
+/// Fields in declaration order.
+fn sorted_fields(fields: &Fields) -> Vec<(Symbol, &move_model_2::summary::Field)> {
+    let mut fields: Vec<_> = fields.fields.iter().map(|(name, f)| (*name, f)).collect();
+    fields.sort_by_key(|(_, field)| field.index);
+    fields
+}
+
+/// Variants in declaration order.
+fn sorted_variants(enum_: &Enum) -> Vec<(Symbol, &move_model_2::summary::Variant)> {
+    let mut variants: Vec<_> = enum_.variants.iter().map(|(name, v)| (*name, v)).collect();
+    variants.sort_by_key(|(_, variant)| variant.index);
+    variants
+}
+
+/// `(u64, bool)`, ` { a: u64, b: bool }` or nothing without fields.
+fn inline_fields(fields: &Fields) -> String {
+    if fields.fields.is_empty() {
+        return String::new();
+    }
+    let fields_ = sorted_fields(fields);
+    if fields.positional_fields {
+        let types: Vec<String> = fields_
+            .iter()
+            .map(|(_, field)| type_to_string(&field.type_))
+            .collect();
+        format!("({})", types.join(", "))
+    } else {
+        let named: Vec<String> = fields_
+            .iter()
+            .map(|(name, field)| format!("{}: {}", name, type_to_string(&field.type_)))
+            .collect();
+        format!(" {{ {} }}", named.join(", "))
+    }
+}
+
+/// Named fields with their doc comments, one per line.
+fn push_fields(out: &mut String, fields: &Fields, indent: &str) {
+    for (name, field) in sorted_fields(fields) {
+        push_doc(out, field.doc.as_deref(), indent);
+        out.push_str(&format!(
+            "{}{}: {},\n",
+            indent,
+            name,
+            type_to_string(&field.type_)
+        ));
+    }
+}
+
+fn push_doc(out: &mut String, doc: Option<&str>, indent: &str) {
+    let Some(doc) = doc else {
+        return;
+    };
+    for line in doc.trim_end().lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            out.push_str(&format!("{}///\n", indent));
+        } else {
+            out.push_str(&format!("{}/// {}\n", indent, line.trim_start()));
+        }
+    }
+}
+
+/// One `#[...]` line per attribute, e.g. `#[test_only]` or `#[allow(lint)]`.
+fn push_attributes(out: &mut String, attributes: &[Attribute], indent: &str) {
+    for attribute in attributes {
+        out.push_str(&format!(
+            "{}#[{}]\n",
+            indent,
+            attribute_to_string(attribute)
+        ));
+    }
+}
+
+fn attribute_to_string(attribute: &Attribute) -> String {
+    match attribute {
+        Attribute::Name(name) => name.to_string(),
+        Attribute::Assigned(name, value) => format!("{} = {}", name, value),
+        Attribute::Parameterized(name, inner) => {
+            let inner: Vec<String> = inner.iter().map(attribute_to_string).collect();
+            format!("{}({})", name, inner.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pool_module;
+    use move_model_2::summary::{Ability, Datatype, ModuleId, Parameter, TParam, TypeArgument};
+
+    fn path((_, module): &ModuleKey) -> String {
+        format!("pools::{}", module)
+    }
+
+    /// `0x2::module::name<arguments>`
+    fn datatype(module: &str, name: &str, arguments: Vec<Type>) -> Type {
+        Type::Datatype(Box::new(Datatype {
+            module: ModuleId {
+                address: pool_module().id.address,
+                name: Symbol::from(module),
+            },
+            name: Symbol::from(name),
+            type_arguments: arguments
+                .into_iter()
+                .map(|argument| TypeArgument {
+                    phantom: false,
+                    argument,
+                })
+                .collect(),
+        }))
+    }
+
+    fn parameter(name: &str, type_: Type) -> Parameter {
+        Parameter {
+            name: Some(Symbol::from(name)),
+            type_,
+        }
+    }
+
+    /// A public macro taking coins and balances of other modules, with
+    /// attributes and a signature too long for one line.
+    fn join_all() -> Function {
+        let t = || Type::NamedTypeParameter(Symbol::from("$T"));
+        let coin = datatype("coin", "Coin", vec![t()]);
+        let balance = datatype("balance", "Balance", vec![t()]);
+        let supply = datatype("balance", "Supply", vec![t()]);
+        Function {
+            index: 1,
+            doc: Some(" Join every coin into `self`.\n\n Aborts when empty.".to_string()),
+            attributes: vec![
+                Attribute::Name(Symbol::from("test_only")),
+                Attribute::Parameterized(
+                    Symbol::from("allow"),
+                    vec![Attribute::Name(Symbol::from("unused_variable"))],
+                ),
+                Attribute::Assigned(Symbol::from("deprecated"), "\"use join\"".to_string()),
+            ],
+            visibility: Visibility::Public,
+            entry: false,
+            macro_: Some(true),
+            type_parameters: vec![TParam {
+                name: Some(Symbol::from("$T")),
+                constraints: AbilitySet([Ability::Copy, Ability::Drop].into()),
+            }],
+            parameters: vec![
+                parameter("self", Type::Reference(true, Box::new(balance.clone()))),
+                parameter("coins", Type::Vector(Box::new(coin))),
+                parameter("supply", Type::Reference(false, Box::new(supply))),
+            ],
+            return_: vec![balance, Type::U64],
+        }
+    }
+
+    #[test]
+    fn renders_the_fixture_module() {
+        assert_eq!(
+            render_module_interface(&pool_module(), path),
+            "\
+/// A pool of balances.
+module pools::pool;
+
+use pools::balance;
+
+public struct Pool<phantom T> has store, key {
+    type: u8,
+    return: u64,
+}
+
+public enum Kind has copy, drop {
+    Fixed(u64),
+}
+
+public(package) fun type<T>(self: &Pool<T>): u8;
+"
+        );
+    }
+
+    #[test]
+    fn renders_attributes_macros_and_long_signatures() {
+        assert_eq!(
+            render_function(Symbol::from("join_all"), &join_all()),
+            "\
+/// Join every coin into `self`.
+///
+/// Aborts when empty.
+#[test_only]
+#[allow(unused_variable)]
+#[deprecated = \"use join\"]
+public macro fun join_all<$T: copy + drop>(
+    self: &mut Balance<$T>,
+    coins: vector<Coin<$T>>,
+    supply: &Supply<$T>,
+): (Balance<$T>, u64)"
+        );
+        let type_ = &pool_module().functions[&Symbol::from("type")];
+        assert_eq!(
+            render_function(Symbol::from("type"), type_),
+            "public(package) fun type<T>(self: &Pool<T>): u8"
+        );
+    }
+
+    #[test]
+    fn renders_positional_and_named_fields() {
+        let module = pool_module();
+        let pool = &module.structs[&Symbol::from("Pool")];
+        assert_eq!(
+            render_struct(Symbol::from("Pool"), pool),
+            "\
+public struct Pool<phantom T> has store, key {
+    type: u8,
+    return: u64,
+}"
+        );
+
+        let mut wrapper = pool.clone();
+        wrapper.fields.positional_fields = true;
+        wrapper.type_parameters.clear();
+        assert_eq!(
+            render_struct(Symbol::from("Wrapper"), &wrapper),
+            "public struct Wrapper(u8, u64) has store, key;"
+        );
+
+        let mut kind = module.enums[&Symbol::from("Kind")].clone();
+        let mut ranged = kind.variants[&Symbol::from("Fixed")].clone();
+        ranged.index = 1;
+        ranged.doc = Some(" Between two bounds.".to_string());
+        ranged.fields = pool.fields.clone();
+        kind.variants.insert(Symbol::from("Ranged"), ranged);
+        assert_eq!(
+            render_enum(Symbol::from("Kind"), &kind),
+            "\
+public enum Kind has copy, drop {
+    Fixed(u64),
+    /// Between two bounds.
+    Ranged {
+        type: u8,
+        return: u64,
+    },
+}"
+        );
+    }
+
+    #[test]
+    fn groups_use_lines_by_module() {
+        let mut module = pool_module();
+        module
+            .functions
+            .insert(Symbol::from("join_all"), join_all());
+        let interface = render_module_interface(&module, path);
+        let uses: Vec<&str> = interface
+            .lines()
+            .filter(|line| line.starts_with("use "))
+            .collect();
+        assert_eq!(
+            uses,
+            [
+                "use pools::balance::{Balance, Supply};",
+                "use pools::coin::Coin;"
+            ]
+        );
+        assert!(interface.ends_with("): (Balance<$T>, u64);\n"));
+    }
+}
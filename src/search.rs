@@ -0,0 +1,352 @@
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::{Packages, Visibility};
+use move_symbol_pool::Symbol;
+use rmcp::schemars;
+
+/// What a search entry is.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Module,
+    Function,
+    Struct,
+    Enum,
+}
+
+/// A module or definition that can be found by name.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SearchEntry {
+    /// Address of the package, as a key of `PackageTree::packages`.
+    pub address: AccountAddress,
+    pub package: Symbol,
+    /// Key of the module in `Package::modules`.
+    pub module: Symbol,
+    pub kind: ItemKind,
+    /// The module name for modules, the definition name otherwise.
+    pub name: Symbol,
+    /// Only functions can be non-public.
+    pub is_public: bool,
+    /// `package::module` or `package::module::name`
+    pub path: String,
+    /// Lowercased characters of `path`.
+    chars: Vec<char>,
+    /// Bonus for a match at each character of `path`.
+    boundaries: Vec<i32>,
+    /// Index in `chars` where `name` starts.
+    name_start: usize,
+}
+
+/// A ranked search result.
+#[derive(Debug, Clone)]
+pub struct SearchHit<'a> {
+    pub entry: &'a SearchEntry,
+    pub score: i32,
+    /// Character indices of `entry.path` that matched the query, ascending.
+    pub positions: Vec<usize>,
+}
+
+/// Fuzzy name index over every module and definition, built once per load.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct SearchIndex {
+    entries: Vec<SearchEntry>,
+}
+
+// Scores of the fuzzy matcher
+const MATCH: i32 = 16;
+const SEGMENT_START: i32 = 24;
+const PATH_START: i32 = 32;
+const CONSECUTIVE: i32 = 20;
+const GAP: i32 = 2;
+/// Gaps longer than this cost no more.
+const MAX_GAP: usize = 8;
+const IN_NAME: i32 = 8;
+const EXACT_NAME: i32 = 200;
+const NAME_PREFIX: i32 = 60;
+
+impl SearchIndex {
+    pub fn new(packages: &Packages) -> Self {
+        let mut entries = Vec::new();
+        for (address, package) in &packages.packages {
+            let Some(package_name) = package.name else {
+                continue;
+            };
+            for (module_key, module) in &package.modules {
+                let entry = |kind, name: Symbol, is_public| {
+                    let path = match kind {
+                        ItemKind::Module => format!("{}::{}", package_name, name),
+                        _ => format!("{}::{}::{}", package_name, module.id.name, name),
+                    };
+                    SearchEntry::new(
+                        *address,
+                        package_name,
+                        *module_key,
+                        kind,
+                        name,
+                        is_public,
+                        path,
+                    )
+                };
+
+                entries.push(entry(ItemKind::Module, module.id.name, true));
+                for (name, function) in &module.functions {
+                    let is_public = matches!(function.visibility, Visibility::Public);
+                    entries.push(entry(ItemKind::Function, *name, is_public));
+                }
+                for name in module.structs.keys() {
+                    entries.push(entry(ItemKind::Struct, *name, true));
+                }
+                for name in module.enums.keys() {
+                    entries.push(entry(ItemKind::Enum, *name, true));
+                }
+            }
+        }
+        Self { entries }
+    }
+
+    /// Entries accepted by `filter` that fuzzily match `query`, best first,
+    /// at most `limit` of them. An empty query matches nothing.
+    ///
+    /// Query characters must appear in order in `package::module::name`.
+    /// Matches at the start of `::`, `_` and camelCase segments, runs of
+    /// consecutive characters and matches inside the name itself rank
+    /// higher.
+    pub fn search(
+        &self,
+        query: &str,
+        filter: impl Fn(&SearchEntry) -> bool,
+        limit: usize,
+    ) -> Vec<SearchHit<'_>> {
+        let query: Vec<char> = query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scratch = Vec::new();
+        let mut hits: Vec<SearchHit> = self
+            .entries
+            .iter()
+            .filter(|entry| filter(entry))
+            .filter_map(|entry| {
+                let (score, positions) = entry.fuzzy_match(&query, &mut scratch)?;
+                Some(SearchHit {
+                    entry,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.entry.path.len().cmp(&b.entry.path.len()))
+                .then(a.entry.path.cmp(&b.entry.path))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+impl SearchEntry {
+    fn new(
+        address: AccountAddress,
+        package: Symbol,
+        module: Symbol,
+        kind: ItemKind,
+        name: Symbol,
+        is_public: bool,
+        path: String,
+    ) -> Self {
+        let original: Vec<char> = path.chars().collect();
+        let chars: Vec<char> = original
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+        let boundaries = original
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let prev = i.checked_sub(1).map(|i| original[i]);
+                match prev {
+                    None => PATH_START,
+                    Some(':') | Some('_') => SEGMENT_START,
+                    Some(prev) if prev.is_lowercase() && c.is_uppercase() => SEGMENT_START,
+                    Some(prev) if !prev.is_ascii_digit() && c.is_ascii_digit() => SEGMENT_START,
+                    _ => 0,
+                }
+            })
+            .collect();
+        let name_start = chars.len() - name.as_str().chars().count();
+        Self {
+            address,
+            package,
+            module,
+            kind,
+            name,
+            is_public,
+            path,
+            chars,
+            boundaries,
+            name_start,
+        }
+    }
+
+    /// Best scoring alignment of `query` as a subsequence of the path, using
+    /// `best` as scratch space.
+    fn fuzzy_match(
+        &self,
+        query: &[char],
+        best: &mut Vec<Option<(i32, usize)>>,
+    ) -> Option<(i32, Vec<usize>)> {
+        let n = self.chars.len();
+        // Cheap rejection before the alignment
+        let mut rest = self.chars.iter();
+        if !query.iter().all(|q| rest.any(|c| c == q)) {
+            return None;
+        }
+
+        // best[i * n + j]: best score with query[i] matched at path position
+        // j, and the position query[i - 1] was matched at
+        best.clear();
+        best.resize(query.len() * n, None);
+        for (i, q) in query.iter().enumerate() {
+            let (prev_rows, rows) = best.split_at_mut(i * n);
+            let prev_row = &prev_rows[(i * n).saturating_sub(n)..];
+            let row = &mut rows[..n];
+            // Best cell of `prev_row` far enough left to pay the whole
+            // `MAX_GAP`, kept up to date as `j` moves right
+            let mut far: Option<(i32, usize)> = None;
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i > 0
+                    && let Some(k) = j.checked_sub(MAX_GAP + 1)
+                    && let Some((prev_score, _)) = prev_row[k]
+                    && far.is_none_or(|(far_score, _)| prev_score >= far_score)
+                {
+                    far = Some((prev_score, k));
+                }
+                if j < i || self.chars[j] != *q {
+                    continue;
+                }
+                let mut bonus = MATCH + self.boundaries[j];
+                if j >= self.name_start {
+                    bonus += IN_NAME;
+                }
+                if i == 0 {
+                    *cell = Some((bonus - GAP * j.min(MAX_GAP) as i32, usize::MAX));
+                    continue;
+                }
+                // Later positions win ties
+                let mut score =
+                    far.map(|(prev_score, k)| (prev_score + bonus - GAP * MAX_GAP as i32, k));
+                let near = j.saturating_sub(MAX_GAP);
+                for (k, prev) in (near..j).zip(&prev_row[near..j]) {
+                    let Some((prev_score, _)) = *prev else {
+                        continue;
+                    };
+                    let link = if k + 1 == j {
+                        CONSECUTIVE
+                    } else {
+                        -GAP * (j - k - 1) as i32
+                    };
+                    let candidate = prev_score + bonus + link;
+                    if score.is_none_or(|(score, _)| candidate >= score) {
+                        score = Some((candidate, k));
+                    }
+                }
+                *cell = score;
+            }
+        }
+
+        let last = query.len() - 1;
+        let (mut j, (mut score, _)) = best[last * n..]
+            .iter()
+            .enumerate()
+            .filter_map(|(j, cell)| cell.map(|cell| (j, cell)))
+            .max_by_key(|(_, (score, _))| *score)?;
+
+        let mut positions = vec![0; query.len()];
+        for i in (0..query.len()).rev() {
+            positions[i] = j;
+            let (_, prev) = best[i * n + j].expect("cell on the best path is set");
+            j = prev;
+        }
+
+        let name = &self.chars[self.name_start..];
+        if name == query {
+            score += EXACT_NAME;
+        } else if name.starts_with(query) {
+            score += NAME_PREFIX;
+        }
+        Some((score, positions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pools;
+
+    fn index() -> SearchIndex {
+        SearchIndex::new(&pools())
+    }
+
+    fn paths<'a>(hits: &[SearchHit<'a>]) -> Vec<&'a str> {
+        hits.iter().map(|hit| hit.entry.path.as_str()).collect()
+    }
+
+    #[test]
+    fn ranks_exact_names_first() {
+        let index = index();
+        let hits = index.search("Kind", |_| true, 10);
+        assert_eq!(paths(&hits)[0], "pools::pool::Kind");
+        assert_eq!(hits[0].positions, [13, 14, 15, 16]);
+
+        let hits = index.search("type", |_| true, 10);
+        assert_eq!(paths(&hits), ["pools::pool::type"]);
+    }
+
+    #[test]
+    fn prefers_segment_starts() {
+        let index = index();
+        let hits = index.search("p p k", |_| true, 10);
+        assert_eq!(paths(&hits), ["pools::pool::Kind"]);
+        assert_eq!(hits[0].positions, [0, 7, 13]);
+
+        let entry = SearchEntry::new(
+            AccountAddress::ZERO,
+            Symbol::from("sui"),
+            Symbol::from("coin"),
+            ItemKind::Function,
+            Symbol::from("newFromCoin"),
+            true,
+            "sui::coin::newFromCoin".to_string(),
+        );
+        let (_, positions) = entry.fuzzy_match(&['f', 'c'], &mut Vec::new()).unwrap();
+        assert_eq!(positions, [14, 18]);
+    }
+
+    #[test]
+    fn filters_before_limiting() {
+        let index = index();
+        assert!(index.search(" ", |_| true, 10).is_empty());
+        assert!(index.search("zz", |_| true, 10).is_empty());
+        assert_eq!(index.search("p", |_| true, 2).len(), 2);
+
+        let hits = index.search("p", |entry| entry.kind == ItemKind::Function, 1);
+        assert_eq!(paths(&hits), ["pools::pool::type"]);
+        assert!(!hits[0].entry.is_public);
+    }
+}
@@ -1,17 +1,40 @@
 use rmcp::{
     handler::server::{tool::ToolRouter, wrapper::Parameters},
     model::{
-        CallToolResult, Content, Implementation, InitializeRequestParam, InitializeResult,
-        ProtocolVersion, ServerCapabilities, ServerInfo,
+        AnnotateAble, CallToolResult, CompleteRequestParam, CompleteResult, Content,
+        GetPromptRequestParam, GetPromptResult, Implementation, InitializeRequestParam,
+        InitializeResult, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        PaginatedRequestParam, ProtocolVersion, RawResource, RawResourceTemplate,
+        ReadResourceRequestParam, ReadResourceResult, ResourceContents,
+        ResourceUpdatedNotificationParam, ServerCapabilities, ServerInfo, SubscribeRequestParam,
+        UnsubscribeRequestParam,
     },
     schemars,
-    service::RequestContext,
+    service::{Peer, RequestContext},
     tool, tool_handler, tool_router,
 };
 
+mod prompts;
+
+use crate::{DefinitionKind, render};
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::Visibility;
+use std::{
+    collections::BTreeSet,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
 #[derive(Clone)]
 pub struct SuiService {
-    packages: crate::PackageTree,
+    packages: crate::reload::LivePackageTree,
+    /// Resource URIs the client subscribed to in this session.
+    subscriptions: Arc<Mutex<BTreeSet<String>>>,
+    /// Whether the task notifying subscribers on reload is running.
+    notifier_started: Arc<AtomicBool>,
     tool_router: ToolRouter<SuiService>,
 }
 
@@ -21,12 +44,36 @@ pub struct ListModulesRequest {
     pub package: String,
 }
 
+/// How definitions are returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// The summary as JSON.
+    #[default]
+    Json,
+    /// Move declarations with doc comments and attributes.
+    Move,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ModuleRequest {
     #[schemars(description = "package name")]
     pub package: String,
     #[schemars(description = "module name")]
     pub module: String,
+    #[schemars(
+        description = "json lists the definition names, move returns the module interface as Move source"
+    )]
+    #[serde(default)]
+    pub format: Format,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct InterfaceRequest {
+    #[schemars(description = "package name")]
+    pub package: String,
+    #[schemars(description = "module name")]
+    pub module: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -37,30 +84,137 @@ pub struct DefinitionRequest {
     pub module: String,
     #[schemars(description = "function/struct/enum name")]
     pub definition: String,
+    #[schemars(description = "json returns the summary, move a Move declaration")]
+    #[serde(default)]
+    pub format: Format,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ModuleGraphRequest {
+    #[schemars(description = "package name")]
+    pub package: String,
+    #[schemars(description = "module name")]
+    pub module: String,
+    #[schemars(description = "follow edges transitively instead of listing direct ones only")]
+    #[serde(default)]
+    pub transitive: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct TypeUsagesRequest {
+    #[schemars(
+        description = "fully qualified struct or enum, e.g. sui::coin::Coin or 0x2::coin::Coin"
+    )]
+    pub datatype: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SignatureSearchRequest {
+    #[schemars(
+        description = "signature query, e.g. (&mut TxContext, Coin<_>) -> Balance<_>. `_` matches any type, T/U/T0 always any type parameter, `&?` either reference mutability; datatypes may be qualified as module::Name or package::module::Name and must be when named like a type parameter, e.g. coin::T"
+    )]
+    pub query: String,
+    #[schemars(
+        description = "contains (default): listed types match distinct parameters/returns in any order; exact: they match all of them in order"
+    )]
+    #[serde(default)]
+    pub mode: crate::MatchMode,
+    #[schemars(description = "only return public functions")]
+    #[serde(default)]
+    pub public_only: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DocSearchRequest {
+    #[schemars(description = "words to look for, e.g. \"split a coin\"")]
+    pub query: String,
+    #[schemars(description = "maximum number of results, 20 by default")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VisibilityFilter {
+    /// Public functions, and all modules, structs and enums
+    Public,
+    /// public(package) functions
+    Package,
+    Private,
+    /// Entry functions of any visibility
+    Entry,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SearchRequest {
+    #[schemars(
+        description = "fuzzy name query, matched in order against package::module::name, e.g. coin split or pool::swap"
+    )]
+    pub query: String,
+    #[schemars(description = "only return items of this kind")]
+    pub kind: Option<crate::ItemKind>,
+    #[schemars(description = "only return items of this package")]
+    pub package: Option<String>,
+    #[schemars(description = "only return functions with this visibility")]
+    pub visibility: Option<VisibilityFilter>,
+    #[schemars(description = "maximum number of results, 20 by default")]
+    pub limit: Option<usize>,
 }
 
 #[tool_router]
 impl SuiService {
-    pub fn new(packages: crate::PackageTree) -> Self {
+    pub fn new(packages: impl Into<crate::reload::LivePackageTree>) -> Self {
         Self {
-            packages,
+            packages: packages.into(),
+            subscriptions: Default::default(),
+            notifier_started: Default::default(),
             tool_router: Self::tool_router(),
         }
     }
 
     #[tool(description = "List packages")]
     async fn list_packages(&self) -> Result<CallToolResult, rmcp::ErrorData> {
-        let packages = self.packages.list_packages();
+        let tree = self.packages.current();
+        let packages = tree.list_packages();
         let out = Content::json(packages)?;
         Ok(CallToolResult::success(vec![out]))
     }
 
+    #[tool(
+        description = "Get the root package, i.e. the one the summaries were generated for, and the contents of root_package_metadata.json"
+    )]
+    async fn get_root_metadata(&self) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let root = tree.root_package();
+        let out = serde_json::json!({
+            "root_package": root.and_then(|addr| tree.index().package_name(&addr)),
+            "root_package_address": root.map(|addr| addr.to_hex_literal()),
+            "metadata": tree.metadata(),
+        });
+        let out = Content::json(out)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
+    #[tool(description = "List packages, modules and files that were skipped while loading")]
+    async fn list_diagnostics(&self) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let diagnostics: Vec<String> = tree
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        let out = Content::json(diagnostics)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
     #[tool(description = "List modules")]
     async fn list_modules(
         &self,
         Parameters(data): Parameters<ListModulesRequest>,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
-        let modules = self.packages.list_modules(&data.package);
+        let tree = self.packages.current();
+        let modules = tree
+            .list_modules(&data.package)
+            .ok_or_else(|| unknown_name("package", &data.package, tree.index().package_names()))?;
         let out = Content::json(modules)?;
         Ok(CallToolResult::success(vec![out]))
     }
@@ -68,9 +222,19 @@ impl SuiService {
     #[tool(description = "Read module")]
     async fn read_module(
         &self,
-        Parameters(ModuleRequest { package, module }): Parameters<ModuleRequest>,
+        Parameters(ModuleRequest {
+            package,
+            module,
+            format,
+        }): Parameters<ModuleRequest>,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
-        let module = self.packages.get_module(&package, &module).unwrap();
+        let tree = self.packages.current();
+        let module = lookup_module(&tree, &package, &module)?;
+
+        if format == Format::Move {
+            let interface = module_interface(&tree, module);
+            return Ok(CallToolResult::success(vec![Content::text(interface)]));
+        }
 
         let out = serde_json::json!({
             "functions": module.functions.keys().collect::<Vec<_>>(),
@@ -82,6 +246,237 @@ impl SuiService {
         Ok(CallToolResult::success(vec![out]))
     }
 
+    #[tool(
+        description = "Read the interface of a module as Move source: use declarations, structs and enums with their abilities and fields, and function signatures, with doc comments and attributes. The most compact view of a whole module API"
+    )]
+    async fn read_module_interface(
+        &self,
+        Parameters(InterfaceRequest { package, module }): Parameters<InterfaceRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let module = lookup_module(&tree, &package, &module)?;
+        let interface = module_interface(&tree, module);
+        Ok(CallToolResult::success(vec![Content::text(interface)]))
+    }
+
+    #[tool(
+        description = "List the modules a module depends on, as package::module. Modules of packages that are not loaded are given by address. Also lists the dependency cycle the module is part of, if any"
+    )]
+    async fn module_dependencies(
+        &self,
+        Parameters(data): Parameters<ModuleGraphRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let module = lookup_module(&tree, &data.package, &data.module)?;
+        let key = (module.id.address, module.id.name);
+        let graph = tree.graph();
+        let dependencies = if data.transitive {
+            graph.transitive_dependencies(&key)
+        } else {
+            graph.dependencies(&key).cloned().unwrap_or_default()
+        };
+        let cycle = graph
+            .cycles()
+            .iter()
+            .find(|cycle| cycle.contains(&key))
+            .map_or(&[][..], Vec::as_slice);
+
+        let out = serde_json::json!({
+            "dependencies": dependencies.iter().map(|key| tree.module_path(key)).collect::<Vec<_>>(),
+            "cycle": cycle.iter().map(|key| tree.module_path(key)).collect::<Vec<_>>(),
+        });
+        let out = Content::json(out)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
+    #[tool(description = "List the modules that depend on a module, as package::module")]
+    async fn module_dependents(
+        &self,
+        Parameters(data): Parameters<ModuleGraphRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let module = lookup_module(&tree, &data.package, &data.module)?;
+        let key = (module.id.address, module.id.name);
+        let graph = tree.graph();
+        let dependents = if data.transitive {
+            graph.transitive_dependents(&key)
+        } else {
+            graph.dependents(&key).cloned().unwrap_or_default()
+        };
+
+        let dependents: Vec<String> = dependents.iter().map(|key| tree.module_path(key)).collect();
+        let out = Content::json(dependents)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
+    #[tool(
+        description = "Find every function parameter, return type, struct field and enum variant field that mentions a struct or enum, also inside vectors, references and type arguments"
+    )]
+    async fn find_type_usages(
+        &self,
+        Parameters(TypeUsagesRequest { datatype }): Parameters<TypeUsagesRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let parts: Vec<&str> = datatype.split("::").collect();
+        let [package, module, name] = parts[..] else {
+            return Err(rmcp::ErrorData::invalid_params(
+                "Expected a fully qualified package::module::Type",
+                None,
+            ));
+        };
+        let package = AccountAddress::from_hex_literal(package)
+            .ok()
+            .and_then(|address| tree.index().package_name(&address))
+            .unwrap_or(package);
+        let module_ = lookup_module(&tree, package, module)?;
+        let Some(name) = tree
+            .find_definition(package, module, name)
+            .map(|def| def.name)
+            .filter(|name| module_.structs.contains_key(name) || module_.enums.contains_key(name))
+        else {
+            let known = module_.structs.keys().chain(module_.enums.keys());
+            return Err(unknown_name(
+                "struct or enum",
+                name,
+                known.map(|name| name.as_str()),
+            ));
+        };
+        let usages = tree.datatype_usages(module_, name);
+        let usages: Vec<serde_json::Value> = usages
+            .iter()
+            .map(|usage| {
+                let mut out = serde_json::to_value(&usage.site).unwrap_or_default();
+                out["module"] = tree.module_path(&(usage.address, usage.module)).into();
+                out
+            })
+            .collect();
+        let out = Content::json(usages)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
+    #[tool(description = "Find functions by the types of their parameters and return values")]
+    async fn search_by_signature(
+        &self,
+        Parameters(data): Parameters<SignatureSearchRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let query = crate::SignatureQuery::parse(&data.query)
+            .map_err(|err| rmcp::ErrorData::invalid_params(err.to_string(), None))?;
+        let functions: Vec<String> = tree
+            .search_by_signature(&query, data.mode)
+            .into_iter()
+            .filter(|def| {
+                !data.public_only
+                    || tree
+                        .get_function_by_ref(def)
+                        .is_some_and(|function| matches!(function.visibility, Visibility::Public))
+            })
+            .map(|def| {
+                format!(
+                    "{}::{}",
+                    tree.module_path(&(def.address, def.module)),
+                    def.name
+                )
+            })
+            .collect();
+        let out = Content::json(functions)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
+    #[tool(
+        description = "Full-text search over doc comments and names of modules, functions, structs, enums, variants and fields, best match first"
+    )]
+    async fn search_docs(
+        &self,
+        Parameters(DocSearchRequest { query, limit }): Parameters<DocSearchRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let hits: Vec<serde_json::Value> = tree
+            .search_docs(&query, |_| true, limit.unwrap_or(20))
+            .iter()
+            .map(|hit| {
+                let module_path = tree.module_path(&(hit.address, hit.module));
+                let item = hit.target.item_path();
+                let path = if item.is_empty() {
+                    module_path
+                } else {
+                    format!("{}::{}", module_path, item)
+                };
+                let mut out = serde_json::to_value(&hit.target).unwrap_or_default();
+                out["path"] = path.into();
+                out["score"] = hit.score.into();
+                out
+            })
+            .collect();
+        let out = Content::json(hits)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
+    #[tool(
+        description = "Search modules, functions, structs and enums by fuzzy name. Returns fully qualified names with one-line signatures, best match first"
+    )]
+    async fn search(
+        &self,
+        Parameters(data): Parameters<SearchRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        if let Some(package) = &data.package
+            && tree.get_package(package).is_none()
+        {
+            return Err(unknown_name(
+                "package",
+                package,
+                tree.index().package_names(),
+            ));
+        }
+        let function = |entry: &crate::SearchEntry| {
+            tree.get_package_by_address(&entry.address)?
+                .modules
+                .get(&entry.module)?
+                .functions
+                .get(&entry.name)
+        };
+        let filter = |entry: &crate::SearchEntry| {
+            data.kind.is_none_or(|kind| kind == entry.kind)
+                && data
+                    .package
+                    .as_deref()
+                    .is_none_or(|package| entry.package.as_str() == package)
+                && data.visibility.is_none_or(|visibility| {
+                    if entry.kind != crate::ItemKind::Function {
+                        return matches!(visibility, VisibilityFilter::Public);
+                    }
+                    function(entry).is_some_and(|function| match visibility {
+                        VisibilityFilter::Public => {
+                            matches!(function.visibility, Visibility::Public)
+                        }
+                        VisibilityFilter::Package => {
+                            matches!(function.visibility, Visibility::Friend)
+                        }
+                        VisibilityFilter::Private => {
+                            matches!(function.visibility, Visibility::Private)
+                        }
+                        VisibilityFilter::Entry => function.entry,
+                    })
+                })
+        };
+
+        let hits: Vec<serde_json::Value> = tree
+            .search_index()
+            .search(&data.query, filter, data.limit.unwrap_or(20))
+            .iter()
+            .map(|hit| {
+                serde_json::json!({
+                    "path": hit.entry.path,
+                    "kind": hit.entry.kind,
+                    "signature": one_line_signature(&tree, hit.entry),
+                })
+            })
+            .collect();
+        let out = Content::json(hits)?;
+        Ok(CallToolResult::success(vec![out]))
+    }
+
     #[tool(description = "Read module definition")]
     async fn read_module_definition(
         &self,
@@ -89,36 +484,204 @@ impl SuiService {
             package,
             module,
             definition,
+            format,
         }): Parameters<DefinitionRequest>,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
-        let module = self.packages.get_module(&package, &module).unwrap();
-        let def: move_symbol_pool::Symbol = definition.into();
-        let definition = if let Some(val) = module.functions.get(&def) {
-            ("FUNCTION", serde_json::to_value(val).unwrap())
-        } else if let Some(val) = module.structs.get(&def) {
-            ("STRUCT", serde_json::to_value(val).unwrap())
-        } else if let Some(val) = module.enums.get(&def) {
-            ("ENUM", serde_json::to_value(val).unwrap())
-        } else {
-            return Err(rmcp::ErrorData::internal_error(
-                "Definition not found",
-                None,
-            ));
-        };
+        let tree = self.packages.current();
+        if format == Format::Move {
+            let declaration = definition_move(&tree, &package, &module, &definition)?;
+            return Ok(CallToolResult::success(vec![Content::text(declaration)]));
+        }
+        let definition = definition_json(&tree, &package, &module, &definition)?;
         let out = Content::json(definition)?;
         Ok(CallToolResult::success(vec![out]))
     }
 }
 
+/// Kind tag and JSON of a definition, as returned by `read_module_definition`.
+fn definition_json(
+    tree: &crate::PackageTree,
+    package: &str,
+    module: &str,
+    definition: &str,
+) -> Result<(&'static str, serde_json::Value), rmcp::ErrorData> {
+    let module_ = lookup_module(tree, package, module)?;
+    let Some(def) = tree.find_definition(package, module, definition) else {
+        return Err(unknown_name(
+            "definition",
+            definition,
+            tree.index().definition_names(package, module),
+        ));
+    };
+    let (kind, value) = match def.kind {
+        DefinitionKind::Function => (
+            "FUNCTION",
+            serde_json::to_value(&module_.functions[&def.name]),
+        ),
+        DefinitionKind::Struct => ("STRUCT", serde_json::to_value(&module_.structs[&def.name])),
+        DefinitionKind::Enum => ("ENUM", serde_json::to_value(&module_.enums[&def.name])),
+    };
+    let value = value.map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+    Ok((kind, value))
+}
+
+/// Move interface of a module returned by `lookup_module`.
+fn module_interface(tree: &crate::PackageTree, module: &move_model_2::summary::Module) -> String {
+    render::render_module_interface(module, |key| tree.module_path(key))
+}
+
+/// Move declaration of a definition, as returned by `read_module_definition`.
+fn definition_move(
+    tree: &crate::PackageTree,
+    package: &str,
+    module: &str,
+    definition: &str,
+) -> Result<String, rmcp::ErrorData> {
+    let module_ = lookup_module(tree, package, module)?;
+    let Some(def) = tree.find_definition(package, module, definition) else {
+        return Err(unknown_name(
+            "definition",
+            definition,
+            tree.index().definition_names(package, module),
+        ));
+    };
+    Ok(match def.kind {
+        DefinitionKind::Function => {
+            render::render_function(def.name, &module_.functions[&def.name])
+        }
+        DefinitionKind::Struct => render::render_struct(def.name, &module_.structs[&def.name]),
+        DefinitionKind::Enum => render::render_enum(def.name, &module_.enums[&def.name]),
+    })
+}
+
+const RESOURCE_SCHEME: &str = "sui-summary://";
+
+/// `sui-summary://<package>/<module>` or
+/// `sui-summary://<package>/<module>/<definition>`
+fn resource_uri(package: &str, module: &str, definition: Option<&str>) -> String {
+    match definition {
+        Some(definition) => format!("{}{}/{}/{}", RESOURCE_SCHEME, package, module, definition),
+        None => format!("{}{}/{}", RESOURCE_SCHEME, package, module),
+    }
+}
+
+/// JSON text of the module or definition a resource URI points at.
+fn read_resource_uri(tree: &crate::PackageTree, uri: &str) -> Result<String, rmcp::ErrorData> {
+    let parts: Option<Vec<&str>> = uri
+        .strip_prefix(RESOURCE_SCHEME)
+        .map(|path| path.split('/').collect());
+    let value = match parts.as_deref() {
+        Some([package, module]) => {
+            let module = lookup_module(tree, package, module)?;
+            serde_json::to_value(module)
+                .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?
+        }
+        Some([package, module, definition]) => {
+            let (kind, value) = definition_json(tree, package, module, definition)?;
+            serde_json::json!({ "kind": kind, "definition": value })
+        }
+        _ => {
+            return Err(rmcp::ErrorData::resource_not_found(
+                format!(
+                    "Expected {}<package>/<module>[/<definition>], got {}",
+                    RESOURCE_SCHEME, uri
+                ),
+                None,
+            ));
+        }
+    };
+    serde_json::to_string_pretty(&value)
+        .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
+}
+
+/// `invalid_params` error for a name that does not exist, suggesting close
+/// matches among `known`.
+fn unknown_name<'a>(
+    kind: &str,
+    name: &str,
+    known: impl IntoIterator<Item = &'a str>,
+) -> rmcp::ErrorData {
+    let suggestions = crate::suggest::suggestions(name, known);
+    let message = if suggestions.is_empty() {
+        format!("Unknown {} '{}'", kind, name)
+    } else {
+        let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{}'", s)).collect();
+        format!(
+            "Unknown {} '{}', did you mean {}?",
+            kind,
+            name,
+            quoted.join(" or ")
+        )
+    };
+    rmcp::ErrorData::invalid_params(
+        message,
+        Some(serde_json::json!({
+            "kind": kind,
+            "name": name,
+            "suggestions": suggestions,
+        })),
+    )
+}
+
+/// Look up a module, reporting an unknown package or module with suggestions.
+fn lookup_module<'a>(
+    tree: &'a crate::PackageTree,
+    package: &str,
+    module: &str,
+) -> Result<&'a move_model_2::summary::Module, rmcp::ErrorData> {
+    if tree.get_package(package).is_none() {
+        return Err(unknown_name(
+            "package",
+            package,
+            tree.index().package_names(),
+        ));
+    }
+    tree.get_module(package, module)
+        .ok_or_else(|| unknown_name("module", module, tree.index().module_names(package)))
+}
+
+/// One-line Move declaration of a search entry.
+fn one_line_signature(tree: &crate::PackageTree, entry: &crate::SearchEntry) -> String {
+    let Some(module) = tree
+        .get_package_by_address(&entry.address)
+        .and_then(|package| package.modules.get(&entry.module))
+    else {
+        return String::new();
+    };
+    let signature = match entry.kind {
+        crate::ItemKind::Module => Some(format!("module {}", entry.path)),
+        crate::ItemKind::Function => module
+            .functions
+            .get(&entry.name)
+            .map(|function| render::function_signature(entry.name, function)),
+        crate::ItemKind::Struct => module
+            .structs
+            .get(&entry.name)
+            .map(|struct_| render::struct_signature(entry.name, struct_)),
+        crate::ItemKind::Enum => module
+            .enums
+            .get(&entry.name)
+            .map(|enum_| render::enum_signature(entry.name, enum_)),
+    };
+    signature.unwrap_or_default()
+}
+
 #[tool_handler]
 impl rmcp::ServerHandler for SuiService {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2025_06_18,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .enable_completions()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_resources_list_changed()
+                .build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(
-                "This server provides tools to introspect the definitions and dependencies of a Sui Move project."
+                "This server provides tools to introspect the definitions and dependencies of a Sui Move project, and prompts for common exploration workflows."
                     .to_string(),
             ),
         }
@@ -132,11 +695,213 @@ impl rmcp::ServerHandler for SuiService {
         if let Some(http_request_part) = context.extensions.get::<axum::http::request::Parts>() {
             let initialize_headers = &http_request_part.headers;
             let initialize_uri = &http_request_part.uri;
-            println!(
+            eprintln!(
                 "initialize from http server: headers={:?}, uri={}",
                 initialize_headers, initialize_uri
             );
         }
+        if !self.notifier_started.swap(true, Ordering::SeqCst) {
+            tokio::spawn(notify_on_reload(
+                self.packages.clone(),
+                self.subscriptions.clone(),
+                context.peer.clone(),
+            ));
+        }
         Ok(self.get_info())
     }
+
+    /// One resource per module. Definitions are reachable through the
+    /// `sui-summary://{package}/{module}/{definition}` template.
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<ListResourcesResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let mut resources = Vec::new();
+        for package in tree.list_packages() {
+            for module in tree.list_modules(&package).unwrap_or_default() {
+                let mut resource = RawResource::new(
+                    resource_uri(&package, &module, None),
+                    format!("{}::{}", package, module),
+                );
+                resource.mime_type = Some("application/json".to_string());
+                resources.push(resource.no_annotation());
+            }
+        }
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, rmcp::ErrorData> {
+        let templates = [
+            (
+                "sui-summary://{package}/{module}",
+                "module",
+                "Summary of a module: its functions, structs and enums",
+            ),
+            (
+                "sui-summary://{package}/{module}/{definition}",
+                "definition",
+                "A function, struct or enum of a module",
+            ),
+        ];
+        let resource_templates = templates
+            .into_iter()
+            .map(|(uri_template, name, description)| {
+                RawResourceTemplate {
+                    uri_template: uri_template.to_string(),
+                    name: name.to_string(),
+                    title: None,
+                    description: Some(description.to_string()),
+                    mime_type: Some("application/json".to_string()),
+                }
+                .no_annotation()
+            })
+            .collect();
+        Ok(ListResourceTemplatesResult {
+            resource_templates,
+            next_cursor: None,
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<ListPromptsResult, rmcp::ErrorData> {
+        Ok(ListPromptsResult {
+            prompts: prompts::list(),
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        prompts::get(&tree, &request.name, &request.arguments.unwrap_or_default())
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<CompleteResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        prompts::complete(&tree, &request)
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<ReadResourceResult, rmcp::ErrorData> {
+        let tree = self.packages.current();
+        let text = read_resource_uri(&tree, &uri)?;
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(text, uri)],
+        })
+    }
+
+    async fn subscribe(
+        &self,
+        SubscribeRequestParam { uri }: SubscribeRequestParam,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        read_resource_uri(&self.packages.current(), &uri)?;
+        self.subscriptions
+            .lock()
+            .expect("subscriptions lock poisoned")
+            .insert(uri);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        UnsubscribeRequestParam { uri }: UnsubscribeRequestParam,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        self.subscriptions
+            .lock()
+            .expect("subscriptions lock poisoned")
+            .remove(&uri);
+        Ok(())
+    }
+}
+
+/// How often a session is checked for a closed transport between reloads.
+const PEER_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Tell the client about resources that changed each time the summaries are
+/// reloaded, until the session goes away.
+async fn notify_on_reload(
+    packages: crate::reload::LivePackageTree,
+    subscriptions: Arc<Mutex<BTreeSet<String>>>,
+    peer: Peer<rmcp::RoleServer>,
+) {
+    let mut updates = packages.subscribe();
+    let mut previous = packages.current();
+    let mut peer_check = tokio::time::interval(PEER_CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            changed = updates.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+            _ = peer_check.tick() => {
+                if peer.is_transport_closed() {
+                    return;
+                }
+                continue;
+            }
+        }
+        let current = updates.borrow_and_update().clone();
+        let uris: Vec<String> = subscriptions
+            .lock()
+            .expect("subscriptions lock poisoned")
+            .iter()
+            .cloned()
+            .collect();
+
+        for uri in uris {
+            let before = read_resource_uri(&previous, &uri).ok();
+            let after = read_resource_uri(&current, &uri).ok();
+            if before != after {
+                let param = ResourceUpdatedNotificationParam { uri };
+                if peer.notify_resource_updated(param).await.is_err() {
+                    return;
+                }
+            }
+        }
+        // The resource list has one entry per module
+        if module_names(&previous) != module_names(&current)
+            && peer.notify_resource_list_changed().await.is_err()
+        {
+            return;
+        }
+        previous = current;
+    }
+}
+
+/// `(package, module)` of every loaded module.
+fn module_names(tree: &crate::PackageTree) -> BTreeSet<(String, String)> {
+    tree.list_packages()
+        .into_iter()
+        .flat_map(|package| {
+            tree.list_modules(&package)
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |module| (package.clone(), module))
+        })
+        .collect()
 }
@@ -0,0 +1,353 @@
+//! MCP prompts pre-filled with context from the loaded packages.
+
+use super::{lookup_module, module_interface, unknown_name};
+use crate::{PackageTree, UsageSite, render};
+use move_model_2::summary::{Ability, Function, Module, Visibility};
+use move_symbol_pool::Symbol;
+use rmcp::model::{
+    CompleteRequestParam, CompleteResult, CompletionInfo, GetPromptResult, JsonObject, Prompt,
+    PromptArgument, PromptMessage, PromptMessageRole,
+};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+struct PromptSpec {
+    name: &'static str,
+    description: &'static str,
+    /// Names and descriptions of the arguments, all required.
+    arguments: &'static [(&'static str, &'static str)],
+}
+
+const PROMPTS: [PromptSpec; 3] = [
+    PromptSpec {
+        name: "explain_module",
+        description: "Explain what a module is for, the types it defines and how to use it",
+        arguments: &[("package", "package name"), ("module", "module name")],
+    },
+    PromptSpec {
+        name: "call_from_ptb",
+        description: "Show how to call a public or entry function from a programmable transaction block",
+        arguments: &[
+            ("package", "package name"),
+            ("module", "module name"),
+            ("function", "public or entry function name"),
+        ],
+    },
+    PromptSpec {
+        name: "package_objects",
+        description: "Describe the objects (structs with the key ability) a package defines",
+        arguments: &[("package", "package name")],
+    },
+];
+
+pub(super) fn list() -> Vec<Prompt> {
+    PROMPTS
+        .iter()
+        .map(|prompt| {
+            let arguments = prompt
+                .arguments
+                .iter()
+                .map(|(name, description)| PromptArgument {
+                    name: name.to_string(),
+                    title: None,
+                    description: Some(description.to_string()),
+                    required: Some(true),
+                })
+                .collect();
+            Prompt::new(prompt.name, Some(prompt.description), Some(arguments))
+        })
+        .collect()
+}
+
+pub(super) fn get(
+    tree: &PackageTree,
+    name: &str,
+    arguments: &JsonObject,
+) -> Result<GetPromptResult, rmcp::ErrorData> {
+    let Some(prompt) = PROMPTS.iter().find(|prompt| prompt.name == name) else {
+        return Err(unknown_name(
+            "prompt",
+            name,
+            PROMPTS.iter().map(|prompt| prompt.name),
+        ));
+    };
+    let text = match prompt.name {
+        "explain_module" => explain_module(
+            tree,
+            argument(arguments, "package")?,
+            argument(arguments, "module")?,
+        )?,
+        "call_from_ptb" => call_from_ptb(
+            tree,
+            argument(arguments, "package")?,
+            argument(arguments, "module")?,
+            argument(arguments, "function")?,
+        )?,
+        "package_objects" => package_objects(tree, argument(arguments, "package")?)?,
+        _ => unreachable!("every prompt in PROMPTS is handled"),
+    };
+    Ok(GetPromptResult {
+        description: Some(prompt.description.to_string()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+/// Completion of a prompt argument, or of a `sui-summary://` resource
+/// template variable, against the loaded names. Arguments filled in earlier
+/// narrow modules to a package and functions and definitions to a module.
+pub(super) fn complete(
+    tree: &PackageTree,
+    request: &CompleteRequestParam,
+) -> Result<CompleteResult, rmcp::ErrorData> {
+    let prompt = request.r#ref.as_prompt_name();
+    let filled = |name: &str| {
+        request
+            .context
+            .as_ref()
+            .and_then(|context| context.get_argument(name))
+            .map(String::as_str)
+    };
+
+    let name = request.argument.name.as_str();
+    let candidates: BTreeSet<&str> = match name {
+        "package" => tree.index().package_names().collect(),
+        "module" => match filled("package") {
+            Some(package) => tree.index().module_names(package).collect(),
+            None => tree
+                .index()
+                .package_names()
+                .flat_map(|package| tree.index().module_names(package))
+                .collect(),
+        },
+        "function" | "definition" => {
+            let module = filled("package")
+                .zip(filled("module"))
+                .and_then(|(package, module)| tree.get_module(package, module));
+            match module {
+                Some(module) if name == "function" => module
+                    .functions
+                    .iter()
+                    .filter(|(_, function)| {
+                        prompt != Some("call_from_ptb") || is_callable(function)
+                    })
+                    .map(|(name, _)| name.as_str())
+                    .collect(),
+                Some(module) => module
+                    .functions
+                    .keys()
+                    .chain(module.structs.keys())
+                    .chain(module.enums.keys())
+                    .map(|name| name.as_str())
+                    .collect(),
+                None => BTreeSet::new(),
+            }
+        }
+        _ => BTreeSet::new(),
+    };
+
+    let prefix = request.argument.value.to_lowercase();
+    let matches: Vec<&str> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&prefix))
+        .collect();
+    let total = matches.len();
+    let values = matches
+        .into_iter()
+        .take(CompletionInfo::MAX_VALUES)
+        .map(str::to_string)
+        .collect();
+    let completion = CompletionInfo::with_pagination(
+        values,
+        u32::try_from(total).ok(),
+        total > CompletionInfo::MAX_VALUES,
+    )
+    .map_err(|err| rmcp::ErrorData::internal_error(err, None))?;
+    Ok(CompleteResult { completion })
+}
+
+fn explain_module(
+    tree: &PackageTree,
+    package: &str,
+    module: &str,
+) -> Result<String, rmcp::ErrorData> {
+    let module_ = lookup_module(tree, package, module)?;
+    let key = (module_.id.address, module_.id.name);
+    let paths = |modules: Option<&BTreeSet<crate::ModuleKey>>| {
+        let paths: Vec<String> = modules
+            .into_iter()
+            .flatten()
+            .map(|key| tree.module_path(key))
+            .collect();
+        if paths.is_empty() {
+            "none".to_string()
+        } else {
+            paths.join(", ")
+        }
+    };
+
+    Ok(format!(
+        "Explain the Sui Move module `{path}`: what it is for, the objects and other types it \
+         defines, and the main ways to use it through its public and entry functions. Point out \
+         capabilities, one-time witnesses and other access control patterns, and anything a \
+         caller must be careful about.\n\n\
+         Its interface:\n\n```move\n{interface}```\n\n\
+         It depends on: {dependencies}\n\
+         It is used by: {dependents}",
+        path = tree.module_path(&key),
+        interface = module_interface(tree, module_),
+        dependencies = paths(tree.graph().dependencies(&key)),
+        dependents = paths(tree.graph().dependents(&key)),
+    ))
+}
+
+fn call_from_ptb(
+    tree: &PackageTree,
+    package: &str,
+    module: &str,
+    function: &str,
+) -> Result<String, rmcp::ErrorData> {
+    let module_ = lookup_module(tree, package, module)?;
+    let Some((name, function_)) = tree
+        .find_definition(package, module, function)
+        .and_then(|def| module_.functions.get_key_value(&def.name))
+    else {
+        return Err(unknown_name(
+            "function",
+            function,
+            module_.functions.keys().map(|name| name.as_str()),
+        ));
+    };
+    if !is_callable(function_) {
+        return Err(rmcp::ErrorData::invalid_params(
+            format!(
+                "'{}' is neither public nor entry and cannot be called from a programmable transaction block",
+                function
+            ),
+            None,
+        ));
+    }
+
+    // Declarations of the loaded datatypes in the signature
+    let types = function_
+        .parameters
+        .iter()
+        .map(|param| &param.type_)
+        .chain(&function_.return_);
+    let mut datatypes = BTreeSet::new();
+    for type_ in types {
+        datatypes.extend(crate::usages::datatypes_in(type_));
+    }
+    let declarations: Vec<String> = datatypes
+        .into_iter()
+        .filter_map(|(address, module_name, datatype)| {
+            let module = tree
+                .get_package_by_address(&address)?
+                .modules
+                .get(&module_name)?;
+            let declaration = match (module.structs.get(&datatype), module.enums.get(&datatype)) {
+                (Some(struct_), _) => render::render_struct(datatype, struct_),
+                (None, Some(enum_)) => render::render_enum(datatype, enum_),
+                (None, None) => return None,
+            };
+            Some(format!(
+                "// {}\n{}",
+                tree.module_path(&(address, module_name)),
+                declaration
+            ))
+        })
+        .collect();
+
+    let mut text = format!(
+        "Show how to call `{path}::{name}` from a Sui programmable transaction block, with the \
+         TypeScript SDK and with the `sui client ptb` command. For each argument, explain how to \
+         obtain or create it: whether it is an object passed by reference or by value, a pure \
+         value, or provided by the system like `&mut TxContext` and `&Clock`. Say what to do \
+         with the returned values, if any.\n\n\
+         The call target is `{address}::{module}::{name}`.\n\n\
+         ```move\n{declaration}\n```",
+        path = tree.module_path(&(module_.id.address, module_.id.name)),
+        address = module_.id.address.to_hex_literal(),
+        module = module_.id.name,
+        declaration = render::render_function(*name, function_),
+    );
+    if !declarations.is_empty() {
+        text.push_str(&format!(
+            "\n\nTypes in the signature:\n\n```move\n{}\n```",
+            declarations.join("\n\n")
+        ));
+    }
+    Ok(text)
+}
+
+fn package_objects(tree: &PackageTree, package: &str) -> Result<String, rmcp::ErrorData> {
+    let Some(package_) = tree.get_package(package) else {
+        return Err(unknown_name(
+            "package",
+            package,
+            tree.index().package_names(),
+        ));
+    };
+
+    let mut objects = Vec::new();
+    for module in package_.modules.values() {
+        for (name, struct_) in &module.structs {
+            if !struct_.abilities.0.contains(&Ability::Key) {
+                continue;
+            }
+            let mut object = format!(
+                "// {}\n{}",
+                tree.module_path(&(module.id.address, module.id.name)),
+                render::render_struct(*name, struct_)
+            );
+            let creators = returning_functions(tree, module, *name);
+            if !creators.is_empty() {
+                object.push_str(&format!("\n// Returned by: {}", creators.join(", ")));
+            }
+            objects.push(object);
+        }
+    }
+
+    let mut text = format!(
+        "Describe the objects defined by the Sui package `{}`: what each one represents, whether \
+         it is meant to be owned, shared, frozen or wrapped, which ones have `store` and can be \
+         transferred freely, and how they are created and destroyed.",
+        package
+    );
+    if objects.is_empty() {
+        text.push_str("\n\nThe package defines no structs with the `key` ability.");
+    } else {
+        text.push_str(&format!(
+            "\n\nIts structs with the `key` ability:\n\n```move\n{}\n```",
+            objects.join("\n\n")
+        ));
+    }
+    Ok(text)
+}
+
+/// `module::function` of the loaded functions returning a datatype.
+fn returning_functions(tree: &PackageTree, module: &Module, datatype: Symbol) -> Vec<String> {
+    let functions: BTreeSet<String> = tree
+        .datatype_usages(module, datatype)
+        .iter()
+        .filter_map(|usage| match usage.site {
+            UsageSite::Return { function, .. } => Some(format!(
+                "{}::{}",
+                tree.module_path(&(usage.address, usage.module)),
+                function
+            )),
+            _ => None,
+        })
+        .collect();
+    functions.into_iter().collect()
+}
+
+/// Whether a programmable transaction block can call the function.
+fn is_callable(function: &Function) -> bool {
+    function.entry || matches!(function.visibility, Visibility::Public)
+}
+
+fn argument<'a>(arguments: &'a JsonObject, name: &str) -> Result<&'a str, rmcp::ErrorData> {
+    arguments.get(name).and_then(Value::as_str).ok_or_else(|| {
+        rmcp::ErrorData::invalid_params(format!("Missing argument '{}'", name), None)
+    })
+}
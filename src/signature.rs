@@ -0,0 +1,520 @@
+//! Queries over the shape of function signatures.
+//!
+//! A query lists parameter types in parentheses and return types after `->`,
+//! either part may be left out:
+//!
+//! ```text
+//! (&mut TxContext, Coin<_>) -> Balance<_>
+//! -> (Coin<T>, Coin<T>)
+//! (&? Pool<_, _>)
+//! ```
+//!
+//! - `_` matches any type.
+//! - `T`, `U`, `T0`, ... (one capital letter, optionally followed by digits)
+//!   match any type parameter of the function. A datatype named like this
+//!   must be qualified, e.g. `module::T`.
+//! - `&T` matches immutable references only, `&mut T` mutable references
+//!   only and `&? T` either.
+//! - Datatypes are matched by name and may be qualified as `module::Name`
+//!   or `package::module::Name`, where the package is a name or address.
+//!   Without type arguments a datatype matches any instantiation.
+//! - Primitives and `vector<T>` match themselves.
+
+use crate::PackageIndex;
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::{Function, Type};
+use rmcp::schemars;
+
+/// How the types listed in a query are compared with a function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Every listed type must match a distinct parameter (or return type),
+    /// in any order. Functions may have more.
+    #[default]
+    Contains,
+    /// The listed types must match the parameters (or return types) one to
+    /// one, in order.
+    Exact,
+}
+
+/// A syntax error in a signature query.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} at column {column}")]
+pub struct QueryError {
+    /// 1-based column of the offending token, in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TypePattern {
+    Any,
+    /// A name like `T` or `T0`, matching any type parameter.
+    TypeParameter,
+    Primitive(&'static str),
+    Vector(Box<TypePattern>),
+    /// `None` matches either mutability.
+    Reference(Option<bool>, Box<TypePattern>),
+    Datatype {
+        package: Option<String>,
+        module: Option<String>,
+        name: String,
+        type_arguments: Option<Vec<TypePattern>>,
+    },
+}
+
+/// A parsed signature query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureQuery {
+    parameters: Option<Vec<TypePattern>>,
+    returns: Option<Vec<TypePattern>>,
+}
+
+const PRIMITIVES: [&str; 9] = [
+    "bool", "u8", "u16", "u32", "u64", "u128", "u256", "address", "signer",
+];
+
+impl SignatureQuery {
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            position: 0,
+            end: query.chars().count() + 1,
+        };
+
+        let parameters = if parser.peek() == Some("(") {
+            Some(parser.list()?)
+        } else {
+            None
+        };
+        let returns = if parser.eat("->") {
+            if parser.peek() == Some("(") {
+                Some(parser.list()?)
+            } else {
+                Some(vec![parser.type_()?])
+            }
+        } else {
+            None
+        };
+        if let Some((column, token)) = parser.tokens.get(parser.position) {
+            return Err(QueryError {
+                column: *column,
+                message: format!("unexpected '{}'", token),
+            });
+        }
+        if parameters.is_none() && returns.is_none() {
+            return Err(QueryError {
+                column: 1,
+                message: "expected '(' or '->'".to_string(),
+            });
+        }
+
+        Ok(Self {
+            parameters,
+            returns,
+        })
+    }
+
+    /// Whether `function` has the queried shape. `index` resolves package
+    /// names in qualified datatypes.
+    pub fn matches(&self, index: &PackageIndex, function: &Function, mode: MatchMode) -> bool {
+        let parameters: Vec<&Type> = function.parameters.iter().map(|p| &p.type_).collect();
+        let returns: Vec<&Type> = function.return_.iter().collect();
+        let matches_list = |patterns: &Option<Vec<TypePattern>>, types: &[&Type]| {
+            let Some(patterns) = patterns else {
+                return true;
+            };
+            match mode {
+                MatchMode::Exact => {
+                    patterns.len() == types.len()
+                        && patterns
+                            .iter()
+                            .zip(types)
+                            .all(|(pattern, type_)| pattern.matches(index, type_))
+                }
+                MatchMode::Contains => {
+                    assign(index, patterns, types, &mut vec![false; types.len()])
+                }
+            }
+        };
+        matches_list(&self.parameters, &parameters) && matches_list(&self.returns, &returns)
+    }
+}
+
+/// Match every pattern against a distinct type, backtracking so that an
+/// earlier pattern does not take a type a later one needs.
+fn assign(
+    index: &PackageIndex,
+    patterns: &[TypePattern],
+    types: &[&Type],
+    used: &mut [bool],
+) -> bool {
+    let Some((pattern, rest)) = patterns.split_first() else {
+        return true;
+    };
+    for (i, type_) in types.iter().enumerate() {
+        if used[i] || !pattern.matches(index, type_) {
+            continue;
+        }
+        used[i] = true;
+        if assign(index, rest, types, used) {
+            return true;
+        }
+        used[i] = false;
+    }
+    false
+}
+
+impl TypePattern {
+    fn matches(&self, index: &PackageIndex, type_: &Type) -> bool {
+        match (self, type_) {
+            (Self::Any, _) => true,
+            (Self::TypeParameter, Type::TypeParameter(_) | Type::NamedTypeParameter(_)) => true,
+            (Self::Primitive(name), _) => primitive_name(type_) == Some(*name),
+            (Self::Vector(pattern), Type::Vector(inner)) => pattern.matches(index, inner),
+            (Self::Reference(mutable, pattern), Type::Reference(is_mut, inner)) => {
+                mutable.is_none_or(|mutable| mutable == *is_mut) && pattern.matches(index, inner)
+            }
+            (
+                Self::Datatype {
+                    package,
+                    module,
+                    name,
+                    type_arguments,
+                },
+                Type::Datatype(dt),
+            ) => {
+                dt.name.as_str() == name
+                    && module
+                        .as_deref()
+                        .is_none_or(|module| dt.module.name.as_str() == module)
+                    && package.as_deref().is_none_or(|package| {
+                        index.package_name(&dt.module.address) == Some(package)
+                            || AccountAddress::from_hex_literal(package)
+                                .is_ok_and(|address| address == dt.module.address)
+                    })
+                    && type_arguments.as_ref().is_none_or(|patterns| {
+                        patterns.len() == dt.type_arguments.len()
+                            && patterns
+                                .iter()
+                                .zip(&dt.type_arguments)
+                                .all(|(pattern, arg)| pattern.matches(index, &arg.argument))
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+fn primitive_name(type_: &Type) -> Option<&'static str> {
+    Some(match type_ {
+        Type::Bool => "bool",
+        Type::U8 => "u8",
+        Type::U16 => "u16",
+        Type::U32 => "u32",
+        Type::U64 => "u64",
+        Type::U128 => "u128",
+        Type::U256 => "u256",
+        Type::Address => "address",
+        Type::Signer => "signer",
+        _ => return None,
+    })
+}
+
+fn is_type_parameter(ident: &str) -> bool {
+    let mut chars = ident.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase()) && chars.all(|c| c.is_ascii_digit())
+}
+
+/// Split a query into `(column, token)` pairs.
+fn tokenize(query: &str) -> Result<Vec<(usize, String)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().enumerate().peekable();
+    while let Some((i, c)) = chars.next() {
+        let column = i + 1;
+        match c {
+            c if c.is_whitespace() => {}
+            '(' | ')' | '<' | '>' | ',' | '&' | '?' => tokens.push((column, c.to_string())),
+            '-' | ':' => {
+                let second = if c == '-' { '>' } else { ':' };
+                if chars.next_if(|(_, next)| *next == second).is_none() {
+                    return Err(QueryError {
+                        column,
+                        message: format!("expected '{}{}'", c, second),
+                    });
+                }
+                tokens.push((column, format!("{}{}", c, second)));
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some((_, next)) =
+                    chars.next_if(|(_, next)| next.is_ascii_alphanumeric() || *next == '_')
+                {
+                    ident.push(next);
+                }
+                tokens.push((column, ident));
+            }
+            _ => {
+                return Err(QueryError {
+                    column,
+                    message: format!("unexpected '{}'", c),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, String)>,
+    position: usize,
+    /// Column reported for errors at the end of the query.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens
+            .get(self.position)
+            .map(|(_, token)| token.as_str())
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn error(&self, message: &str) -> QueryError {
+        let column = self
+            .tokens
+            .get(self.position)
+            .map_or(self.end, |(column, _)| *column);
+        QueryError {
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), QueryError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", token)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, QueryError> {
+        match self.peek() {
+            Some(token) if token.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_') => {
+                let ident = token.to_string();
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("expected a type")),
+        }
+    }
+
+    /// `( type, ... )`, allowing a trailing comma.
+    fn list(&mut self) -> Result<Vec<TypePattern>, QueryError> {
+        self.expect("(")?;
+        let mut types = Vec::new();
+        while !self.eat(")") {
+            types.push(self.type_()?);
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        Ok(types)
+    }
+
+    /// `< type, ... >`
+    fn type_arguments(&mut self) -> Result<Vec<TypePattern>, QueryError> {
+        self.expect("<")?;
+        let mut types = vec![self.type_()?];
+        while self.eat(",") {
+            types.push(self.type_()?);
+        }
+        self.expect(">")?;
+        Ok(types)
+    }
+
+    fn type_(&mut self) -> Result<TypePattern, QueryError> {
+        if self.eat("&") {
+            let mutable = if self.eat("mut") {
+                Some(true)
+            } else if self.eat("?") {
+                None
+            } else {
+                Some(false)
+            };
+            return Ok(TypePattern::Reference(mutable, Box::new(self.type_()?)));
+        }
+
+        let mut path = vec![self.ident()?];
+        while self.eat("::") {
+            path.push(self.ident()?);
+        }
+        let type_arguments = if self.peek() == Some("<") {
+            Some(self.type_arguments()?)
+        } else {
+            None
+        };
+
+        if let ([ident], None) = (path.as_slice(), &type_arguments) {
+            if ident == "_" {
+                return Ok(TypePattern::Any);
+            }
+            if is_type_parameter(ident) {
+                return Ok(TypePattern::TypeParameter);
+            }
+            if let Some(primitive) = PRIMITIVES.iter().find(|p| **p == ident.as_str()) {
+                return Ok(TypePattern::Primitive(primitive));
+            }
+        }
+        if let ([ident], Some(arguments)) = (path.as_slice(), &type_arguments)
+            && ident == "vector"
+        {
+            let [element] = arguments.as_slice() else {
+                return Err(self.error("vector takes one type argument"));
+            };
+            return Ok(TypePattern::Vector(Box::new(element.clone())));
+        }
+
+        let name = path.pop().expect("path has at least one segment");
+        let module = path.pop();
+        let package = path.pop();
+        if !path.is_empty() {
+            return Err(self.error("expected at most package::module::Name"));
+        }
+        Ok(TypePattern::Datatype {
+            package,
+            module,
+            name,
+            type_arguments,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{packages, pool_module as module};
+    use move_model_2::summary::{Datatype, Module};
+    use move_symbol_pool::Symbol;
+
+    fn index(module: &Module) -> PackageIndex {
+        PackageIndex::new(&packages([module.clone()]))
+    }
+
+    fn matches(index: &PackageIndex, module: &Module, query: &str, mode: MatchMode) -> bool {
+        let function = &module.functions[&Symbol::from("type")];
+        SignatureQuery::parse(query)
+            .unwrap()
+            .matches(index, function, mode)
+    }
+
+    #[test]
+    fn parses_parameters_and_returns() {
+        let query = SignatureQuery::parse("(&mut TxContext, vector<T>) -> u64").unwrap();
+        assert_eq!(
+            query.parameters,
+            Some(vec![
+                TypePattern::Reference(
+                    Some(true),
+                    Box::new(TypePattern::Datatype {
+                        package: None,
+                        module: None,
+                        name: "TxContext".to_string(),
+                        type_arguments: None,
+                    })
+                ),
+                TypePattern::Vector(Box::new(TypePattern::TypeParameter)),
+            ])
+        );
+        assert_eq!(query.returns, Some(vec![TypePattern::Primitive("u64")]));
+
+        let query = SignatureQuery::parse("-> (pools::pool::Pool<_>, )").unwrap();
+        assert_eq!(query.parameters, None);
+        assert_eq!(
+            query.returns,
+            Some(vec![TypePattern::Datatype {
+                package: Some("pools".to_string()),
+                module: Some("pool".to_string()),
+                name: "Pool".to_string(),
+                type_arguments: Some(vec![TypePattern::Any]),
+            }])
+        );
+    }
+
+    #[test]
+    fn reports_errors_at_char_columns() {
+        let error = |query| SignatureQuery::parse(query).unwrap_err();
+        assert_eq!(error("").column, 1);
+        assert_eq!(error("(u8").column, 4);
+        assert_eq!(error("(a::b::c::D)").column, 12);
+        assert_eq!(
+            error("(é, $)"),
+            QueryError {
+                column: 2,
+                message: "unexpected 'é'".to_string(),
+            }
+        );
+        // U+3000 is whitespace three bytes long.
+        assert_eq!(error("(\u{3000}u8").column, 5);
+        assert_eq!(error("(u8)\u{3000}x").column, 6);
+    }
+
+    #[test]
+    fn matches_references_datatypes_and_type_parameters() {
+        let module = module();
+        let index = index(&module);
+        let contains = |query| matches(&index, &module, query, MatchMode::Contains);
+
+        assert!(contains("(&Pool<_>) -> u8"));
+        assert!(contains("(&? pool::Pool<T>)"));
+        assert!(!contains("(pools::pool::Pool)"));
+        assert!(contains("(&pools::pool::Pool)"));
+        assert!(contains("(&0x2::pool::Pool)"));
+        assert!(!contains("(&other::pool::Pool)"));
+        assert!(!contains("(&mut Pool<_>)"));
+        assert!(!contains("(&Pool<u8>)"));
+        assert!(!contains("-> u64"));
+        assert!(contains("()"));
+        assert!(!contains("(_, _)"));
+    }
+
+    #[test]
+    fn exact_mode_requires_every_type_in_order() {
+        let module = module();
+        let index = index(&module);
+        let exact = |query| matches(&index, &module, query, MatchMode::Exact);
+
+        assert!(exact("(&Pool<_>) -> (u8)"));
+        assert!(!exact("()"));
+        assert!(!exact("-> ()"));
+    }
+
+    #[test]
+    fn single_capitals_are_always_type_parameters() {
+        let mut module = module();
+        let pool = module.structs[&Symbol::from("Pool")].clone();
+        module.structs.insert(Symbol::from("T"), pool);
+        let function = module.functions.get_mut(&Symbol::from("type")).unwrap();
+        function.return_ = vec![Type::Datatype(Box::new(Datatype {
+            module: module.id,
+            name: Symbol::from("T"),
+            type_arguments: Vec::new(),
+        }))];
+        let index = index(&module);
+        let contains = |query| matches(&index, &module, query, MatchMode::Contains);
+
+        assert!(contains("(&Pool<T>)"));
+        assert!(!contains("-> T"));
+        assert!(contains("-> pool::T"));
+    }
+}
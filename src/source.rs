@@ -0,0 +1,402 @@
+use crate::{
+    cache::hash_bytes,
+    parser::{Result, SummaryError},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{Cursor, Read},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+const ADDRESS_MAPPING_FILE: &str = "address_mapping.json";
+
+/// A `package_summaries` tree, either on disk or unpacked in memory from an archive.
+///
+/// All paths passed to a `Source` are relative to the summaries root.
+pub(crate) enum Source {
+    Dir {
+        root: PathBuf,
+        /// Content hashes of the files read so far, keyed by `rel`.
+        read: Mutex<BTreeMap<PathBuf, u64>>,
+    },
+    Archive(ArchiveSource),
+}
+
+pub(crate) struct ArchiveSource {
+    /// Path of the archive file.
+    archive: PathBuf,
+    /// Content hash of the archive file.
+    hash: u64,
+    /// Directory inside the archive that holds address_mapping.json.
+    root: PathBuf,
+    /// Files below `root`, keyed by their path relative to it.
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+/// An entry returned by `Source::entries`.
+pub(crate) struct Entry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+#[derive(Clone, Copy)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `path` is named like an archive `Source::open` can read.
+pub(crate) fn is_archive(path: &Path) -> bool {
+    ArchiveKind::detect(path).is_some()
+}
+
+impl Source {
+    /// Open a summaries directory or a `.zip`, `.tar`, `.tar.gz` archive.
+    pub fn open(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(SummaryError::MissingDirectory {
+                path: path.to_path_buf(),
+            });
+        }
+
+        if path.is_dir() {
+            return Ok(Self::Dir {
+                root: path.to_path_buf(),
+                read: Mutex::default(),
+            });
+        }
+
+        match ArchiveKind::detect(path) {
+            Some(kind) => Ok(Self::Archive(ArchiveSource::read(path, kind)?)),
+            None => Err(SummaryError::NotADirectory {
+                path: path.to_path_buf(),
+            }),
+        }
+    }
+
+    /// The path to show to users for a file inside the source.
+    pub fn display_path(&self, rel: &Path) -> PathBuf {
+        match self {
+            Self::Dir { root, .. } => root.join(rel),
+            Self::Archive(archive) => archive.archive.join(&archive.root).join(rel),
+        }
+    }
+
+    /// Read a file, returning `None` if it does not exist.
+    pub fn read_to_string(&self, rel: &Path) -> Result<Option<String>> {
+        match self {
+            Self::Dir { root, read } => {
+                let path = root.join(rel);
+                if !path.exists() {
+                    return Ok(None);
+                }
+                let content = fs::read_to_string(&path)
+                    .map_err(|source| SummaryError::Unreadable { path, source })?;
+                read.lock()
+                    .expect("file hashes lock poisoned")
+                    .insert(rel.to_path_buf(), hash_bytes(content.as_bytes()));
+                Ok(Some(content))
+            }
+            Self::Archive(archive) => {
+                let Some(bytes) = archive.files.get(rel) else {
+                    return Ok(None);
+                };
+                String::from_utf8(bytes.clone())
+                    .map(Some)
+                    .map_err(|err| SummaryError::Unreadable {
+                        path: self.display_path(rel),
+                        source: std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+                    })
+            }
+        }
+    }
+
+    /// Content hashes of the files read so far, see
+    /// `ParsedSummaries::file_hashes`.
+    pub fn file_hashes(&self) -> BTreeMap<PathBuf, u64> {
+        match self {
+            Self::Dir { read, .. } => read.lock().expect("file hashes lock poisoned").clone(),
+            Self::Archive(archive) => BTreeMap::from([(PathBuf::new(), archive.hash)]),
+        }
+    }
+
+    /// The immediate children of a directory, in name order.
+    pub fn entries(&self, rel: &Path) -> Result<Vec<Entry>> {
+        match self {
+            Self::Dir { root, .. } => {
+                let dir = root.join(rel);
+                let unreadable = |source| SummaryError::Unreadable {
+                    path: dir.clone(),
+                    source,
+                };
+                let mut entries = Vec::new();
+                for entry in fs::read_dir(&dir).map_err(unreadable)? {
+                    let entry = entry.map_err(unreadable)?;
+                    entries.push(Entry {
+                        path: rel.join(entry.file_name()),
+                        is_dir: entry.path().is_dir(),
+                    });
+                }
+                entries.sort_by(|a, b| a.path.cmp(&b.path));
+                Ok(entries)
+            }
+            Self::Archive(archive) => {
+                let mut dirs = BTreeSet::new();
+                let mut files = BTreeSet::new();
+                for file in archive.files.keys() {
+                    let Ok(rest) = file.strip_prefix(rel) else {
+                        continue;
+                    };
+                    let mut components = rest.components();
+                    let Some(first) = components.next() else {
+                        continue;
+                    };
+                    if components.next().is_some() {
+                        dirs.insert(rel.join(first));
+                    } else {
+                        files.insert(rel.join(first));
+                    }
+                }
+                let mut entries: Vec<Entry> = dirs
+                    .into_iter()
+                    .map(|path| Entry { path, is_dir: true })
+                    .chain(files.into_iter().map(|path| Entry {
+                        path,
+                        is_dir: false,
+                    }))
+                    .collect();
+                entries.sort_by(|a, b| a.path.cmp(&b.path));
+                Ok(entries)
+            }
+        }
+    }
+}
+
+impl ArchiveSource {
+    fn read(path: &Path, kind: ArchiveKind) -> Result<Self> {
+        let invalid =
+            |err: Box<dyn std::error::Error + Send + Sync>| SummaryError::InvalidArchive {
+                path: path.to_path_buf(),
+                source: err,
+            };
+
+        let bytes = fs::read(path).map_err(|source| SummaryError::Unreadable {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let hash = hash_bytes(&bytes);
+        let file = Cursor::new(bytes);
+
+        let mut all_files = BTreeMap::new();
+        match kind {
+            ArchiveKind::Zip => {
+                let mut zip = zip::ZipArchive::new(file).map_err(|err| invalid(err.into()))?;
+                for i in 0..zip.len() {
+                    let mut entry = zip.by_index(i).map_err(|err| invalid(err.into()))?;
+                    if entry.is_dir() {
+                        continue;
+                    }
+                    let Some(name) = entry.enclosed_name() else {
+                        continue;
+                    };
+                    let mut bytes = Vec::new();
+                    entry
+                        .read_to_end(&mut bytes)
+                        .map_err(|err| invalid(err.into()))?;
+                    all_files.insert(name, bytes);
+                }
+            }
+            ArchiveKind::Tar => {
+                read_tar(file, &mut all_files).map_err(|err| invalid(err.into()))?
+            }
+            ArchiveKind::TarGz => read_tar(flate2::read::GzDecoder::new(file), &mut all_files)
+                .map_err(|err| invalid(err.into()))?,
+        }
+
+        // The summaries are usually nested in a package_summaries/ folder, so
+        // use the shallowest directory that holds an address mapping as root.
+        let root = all_files
+            .keys()
+            .filter(|file| {
+                file.file_name()
+                    .is_some_and(|name| name == ADDRESS_MAPPING_FILE)
+            })
+            .filter_map(|file| file.parent())
+            .min_by_key(|dir| dir.components().count())
+            .map(Path::to_path_buf)
+            .ok_or_else(|| SummaryError::MissingAddressMapping {
+                path: path.join(ADDRESS_MAPPING_FILE),
+            })?;
+
+        let files = all_files
+            .into_iter()
+            .filter_map(|(file, bytes)| {
+                let rel = file.strip_prefix(&root).ok()?.to_path_buf();
+                Some((rel, bytes))
+            })
+            .collect();
+
+        Ok(Self {
+            archive: path.to_path_buf(),
+            hash,
+            root,
+            files,
+        })
+    }
+}
+
+fn read_tar<R: Read>(reader: R, files: &mut BTreeMap<PathBuf, Vec<u8>>) -> std::io::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        files.insert(path, bytes);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::io::Write;
+
+    /// Files of a summaries folder nested in an outer directory, as packed by
+    /// `tar czf summaries.tgz build/`.
+    const FILES: [(&str, &str); 4] = [
+        ("build/README", "not a summary"),
+        ("build/package_summaries/address_mapping.json", "{}"),
+        ("build/package_summaries/pool/pool.json", "{}"),
+        // A nested mapping must not be taken as the root
+        (
+            "build/package_summaries/pool/deps/address_mapping.json",
+            "{}",
+        ),
+    ];
+
+    /// Write `bytes` as the archive `name` inside `dir`.
+    fn archive(dir: &TempDir, name: &str, bytes: &[u8]) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn tar(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, content) in files {
+            writer
+                .start_file(*path, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn assert_rooted_at_summaries(source: &Source) {
+        let Source::Archive(archive) = source else {
+            panic!("expected an archive source");
+        };
+        assert_eq!(archive.root, Path::new("build/package_summaries"));
+        let entries: Vec<_> = source
+            .entries(Path::new(""))
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.path, entry.is_dir))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (PathBuf::from("address_mapping.json"), false),
+                (PathBuf::from("pool"), true),
+            ]
+        );
+        assert_eq!(
+            source
+                .read_to_string(Path::new("pool/pool.json"))
+                .unwrap()
+                .as_deref(),
+            Some("{}")
+        );
+        assert_eq!(source.read_to_string(Path::new("README")).unwrap(), None);
+    }
+
+    #[test]
+    fn roots_tar_archives_at_the_shallowest_address_mapping() {
+        let dir = TempDir::new("source-summaries-tar");
+        let archive = archive(&dir, "summaries.tar", &tar(&FILES));
+        assert_rooted_at_summaries(&Source::open(&archive).unwrap());
+    }
+
+    #[test]
+    fn roots_compressed_tar_archives_at_the_shallowest_address_mapping() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(&tar(&FILES)).unwrap();
+        let dir = TempDir::new("source-summaries-tar-gz");
+        let archive = archive(&dir, "summaries.tar.gz", &encoder.finish().unwrap());
+        assert_rooted_at_summaries(&Source::open(&archive).unwrap());
+    }
+
+    #[test]
+    fn roots_zip_archives_at_the_shallowest_address_mapping() {
+        let dir = TempDir::new("source-summaries-zip");
+        let archive = archive(&dir, "summaries.zip", &zip(&FILES));
+        assert_rooted_at_summaries(&Source::open(&archive).unwrap());
+    }
+
+    #[test]
+    fn rejects_archives_without_address_mapping() {
+        let dir = TempDir::new("source-empty-tar");
+        let archive = archive(&dir, "empty.tar", &tar(&FILES[..1]));
+        assert!(matches!(
+            Source::open(&archive),
+            Err(SummaryError::MissingAddressMapping { .. })
+        ));
+    }
+
+    #[test]
+    fn recognizes_archives_by_their_full_suffix() {
+        for name in ["a.zip", "a.tar", "a.tar.gz", "A.TGZ"] {
+            assert!(is_archive(Path::new(name)), "{name}");
+        }
+        for name in ["a.gz", "a.json.gz", "tar.gz", "a.json"] {
+            assert!(!is_archive(Path::new(name)), "{name}");
+        }
+    }
+}
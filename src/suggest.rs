@@ -0,0 +1,66 @@
+/// Up to three `candidates` close to `name` by edit distance, closest first.
+/// Comparison ignores case, and only candidates within roughly a third of
+/// the name's length are suggested.
+pub fn suggestions<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    let mut close: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let distance = edit_distance(&name, &candidate.to_lowercase());
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+    close.sort();
+    close.dedup_by(|a, b| a.1 == b.1);
+    close
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_edit_distance_over_chars() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("café", "cafe"), 1);
+        assert_eq!(edit_distance("coin", "coin"), 0);
+    }
+
+    #[test]
+    fn suggests_closest_names_ignoring_case() {
+        let candidates = [
+            "balance", "Balance", "coin", "coins", "join", "pay", "transfer",
+        ];
+        assert_eq!(suggestions("Coin", candidates), ["coin", "coins", "join"]);
+        assert_eq!(suggestions("BALANCE", candidates), ["Balance", "balance"]);
+        assert!(suggestions("object", candidates).is_empty());
+    }
+
+    #[test]
+    fn does_not_repeat_candidates() {
+        assert_eq!(suggestions("coin", ["coins", "coins"]), ["coins"]);
+    }
+}
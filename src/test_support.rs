@@ -0,0 +1,63 @@
+//! Fixtures shared by the unit tests.
+
+use move_model_2::summary::{Module, Package, Packages};
+use move_symbol_pool::Symbol;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The `pool` module of tests/fixtures/schema/v1.json, at `0x2`.
+pub(crate) fn pool_module() -> Module {
+    serde_json::from_str(include_str!("../tests/fixtures/schema/v1.json")).unwrap()
+}
+
+/// `modules` as one package named `pools`, at the address of the first one.
+pub(crate) fn packages(modules: impl IntoIterator<Item = Module>) -> Packages {
+    let modules: std::collections::BTreeMap<_, _> = modules
+        .into_iter()
+        .map(|module| (module.id.name, module))
+        .collect();
+    let address = modules
+        .values()
+        .next()
+        .expect("at least one module")
+        .id
+        .address;
+    let package = Package {
+        name: Some(Symbol::from("pools")),
+        modules,
+    };
+    Packages {
+        packages: [(address, package)].into(),
+    }
+}
+
+/// The `pools` package holding only `pool_module`.
+pub(crate) fn pools() -> Packages {
+    packages([pool_module()])
+}
+
+/// A directory in the temp dir, removed with its contents on drop.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` must be unique among the tests, which run in parallel.
+    pub(crate) fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("sui-summary-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
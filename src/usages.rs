@@ -0,0 +1,161 @@
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::{Fields, Packages, Type};
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// A struct or enum by the address and name of its defining module and its
+/// own name.
+pub type DatatypeKey = (AccountAddress, Symbol, Symbol);
+
+/// Where a datatype is mentioned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UsageSite {
+    /// The `index`th parameter of a function.
+    Parameter { function: Symbol, index: usize },
+    /// The `index`th return type of a function.
+    Return { function: Symbol, index: usize },
+    /// A field of a struct.
+    Field { datatype: Symbol, field: Symbol },
+    /// A field of an enum variant.
+    VariantField {
+        datatype: Symbol,
+        variant: Symbol,
+        field: Symbol,
+    },
+}
+
+/// A use of a datatype inside a loaded module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeUsage {
+    /// Address of the package, as a key of `PackageTree::packages`.
+    pub address: AccountAddress,
+    /// Key of the module in `Package::modules`.
+    pub module: Symbol,
+    #[serde(flatten)]
+    pub site: UsageSite,
+}
+
+/// Every parameter, return type and field that mentions a datatype, also
+/// nested inside vectors, references and type arguments.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TypeUsageIndex {
+    usages: HashMap<DatatypeKey, Vec<TypeUsage>>,
+}
+
+impl TypeUsageIndex {
+    pub fn new(packages: &Packages) -> Self {
+        let mut usages: HashMap<DatatypeKey, Vec<TypeUsage>> = HashMap::new();
+        for (address, package) in &packages.packages {
+            for (module_key, module) in &package.modules {
+                let mut record = |type_: &Type, site: UsageSite| {
+                    for datatype in datatypes_in(type_) {
+                        usages.entry(datatype).or_default().push(TypeUsage {
+                            address: *address,
+                            module: *module_key,
+                            site: site.clone(),
+                        });
+                    }
+                };
+
+                let mut functions: Vec<_> = module.functions.iter().collect();
+                functions.sort_by_key(|(_, function)| function.index);
+                for (name, function) in functions {
+                    for (index, parameter) in function.parameters.iter().enumerate() {
+                        let site = UsageSite::Parameter {
+                            function: *name,
+                            index,
+                        };
+                        record(&parameter.type_, site);
+                    }
+                    for (index, type_) in function.return_.iter().enumerate() {
+                        let site = UsageSite::Return {
+                            function: *name,
+                            index,
+                        };
+                        record(type_, site);
+                    }
+                }
+                let mut structs: Vec<_> = module.structs.iter().collect();
+                structs.sort_by_key(|(_, struct_)| struct_.index);
+                for (name, struct_) in structs {
+                    for_each_field(&struct_.fields, |field, type_| {
+                        let site = UsageSite::Field {
+                            datatype: *name,
+                            field,
+                        };
+                        record(type_, site);
+                    });
+                }
+                let mut enums: Vec<_> = module.enums.iter().collect();
+                enums.sort_by_key(|(_, enum_)| enum_.index);
+                for (name, enum_) in enums {
+                    let mut variants: Vec<_> = enum_.variants.iter().collect();
+                    variants.sort_by_key(|(_, variant)| variant.index);
+                    for (variant_name, variant) in variants {
+                        for_each_field(&variant.fields, |field, type_| {
+                            let site = UsageSite::VariantField {
+                                datatype: *name,
+                                variant: *variant_name,
+                                field,
+                            };
+                            record(type_, site);
+                        });
+                    }
+                }
+            }
+        }
+        Self { usages }
+    }
+
+    /// Uses of a datatype, in package, module and declaration order.
+    pub fn usages(&self, datatype: &DatatypeKey) -> &[TypeUsage] {
+        self.usages.get(datatype).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Call `f` on every field, in declaration order.
+fn for_each_field(fields: &Fields, mut f: impl FnMut(Symbol, &Type)) {
+    let mut fields: Vec<_> = fields.fields.iter().collect();
+    fields.sort_by_key(|(_, field)| field.index);
+    for (name, field) in fields {
+        f(*name, &field.type_);
+    }
+}
+
+/// The distinct datatypes mentioned anywhere in `type_`.
+pub(crate) fn datatypes_in(type_: &Type) -> BTreeSet<DatatypeKey> {
+    fn walk(type_: &Type, out: &mut BTreeSet<DatatypeKey>) {
+        match type_ {
+            Type::Datatype(dt) => {
+                out.insert((dt.module.address, dt.module.name, dt.name));
+                for argument in &dt.type_arguments {
+                    walk(&argument.argument, out);
+                }
+            }
+            Type::Vector(inner) | Type::Reference(_, inner) => walk(inner, out),
+            Type::Tuple(types) => types.iter().for_each(|inner| walk(inner, out)),
+            Type::Fun(args, ret) => {
+                args.iter().for_each(|inner| walk(inner, out));
+                walk(ret, out);
+            }
+            Type::Bool
+            | Type::U8
+            | Type::U16
+            | Type::U32
+            | Type::U64
+            | Type::U128
+            | Type::U256
+            | Type::Address
+            | Type::Signer
+            | Type::TypeParameter(_)
+            | Type::NamedTypeParameter(_)
+            | Type::Any => {}
+        }
+    }
+
+    let mut out = BTreeSet::new();
+    walk(type_, &mut out);
+    out
+}
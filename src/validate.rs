@@ -0,0 +1,301 @@
+use move_core_types::account_address::AccountAddress;
+use move_model_2::summary::{Fields, Module, Packages, Type};
+use move_symbol_pool::Symbol;
+
+/// A cross reference in the loaded packages that does not resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// A type used in `location` names a struct or enum that is not loaded.
+    DanglingType {
+        location: String,
+        address: AccountAddress,
+        module: Symbol,
+        name: Symbol,
+    },
+    /// A module lists a dependency on a module that is not loaded.
+    MissingDependency {
+        module: String,
+        address: AccountAddress,
+        dependency: Symbol,
+    },
+    /// A module whose own address differs from the address its package was
+    /// loaded under in `address_mapping.json`.
+    AddressMismatch {
+        module: String,
+        expected: AccountAddress,
+        found: AccountAddress,
+    },
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DanglingType {
+                location,
+                address,
+                module,
+                name,
+            } => write!(
+                f,
+                "{} references unknown type {}::{}::{}",
+                location,
+                address.to_hex_literal(),
+                module,
+                name
+            ),
+            Self::MissingDependency {
+                module,
+                address,
+                dependency,
+            } => write!(
+                f,
+                "{} depends on unknown module {}::{}",
+                module,
+                address.to_hex_literal(),
+                dependency
+            ),
+            Self::AddressMismatch {
+                module,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has address {} but its package is mapped to {}",
+                module,
+                found.to_hex_literal(),
+                expected.to_hex_literal()
+            ),
+        }
+    }
+}
+
+/// Check every type reference, dependency and module address in `packages`.
+pub(crate) fn validate(packages: &Packages) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    for (address, package) in &packages.packages {
+        let package_name = package
+            .name
+            .map_or_else(|| address.to_hex_literal(), |name| name.to_string());
+        for module in package.modules.values() {
+            let module_path = format!("{}::{}", package_name, module.id.name);
+
+            if module.id.address != *address {
+                issues.push(ValidationIssue::AddressMismatch {
+                    module: module_path.clone(),
+                    expected: *address,
+                    found: module.id.address,
+                });
+            }
+
+            for dependency in &module.immediate_dependencies {
+                if find_module(packages, &dependency.address, dependency.name).is_none() {
+                    issues.push(ValidationIssue::MissingDependency {
+                        module: module_path.clone(),
+                        address: dependency.address,
+                        dependency: dependency.name,
+                    });
+                }
+            }
+
+            let mut check = |item: &str, type_: &Type| {
+                let location = format!("{}::{}", module_path, item);
+                check_type(packages, &location, type_, &mut issues);
+            };
+            for (name, function) in &module.functions {
+                for parameter in &function.parameters {
+                    check(name.as_str(), &parameter.type_);
+                }
+                for type_ in &function.return_ {
+                    check(name.as_str(), type_);
+                }
+            }
+            for (name, struct_) in &module.structs {
+                for_each_field_type(&struct_.fields, |type_| check(name.as_str(), type_));
+            }
+            for (name, enum_) in &module.enums {
+                for (variant_name, variant) in &enum_.variants {
+                    let item = format!("{}::{}", name, variant_name);
+                    for_each_field_type(&variant.fields, |type_| check(&item, type_));
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+fn for_each_field_type(fields: &Fields, mut f: impl FnMut(&Type)) {
+    for field in fields.fields.values() {
+        f(&field.type_);
+    }
+}
+
+/// Find a module by its own id. Packages are keyed by the address they were
+/// loaded under, which is the module address unless an `AddressMismatch`
+/// was reported for it.
+fn find_module<'a>(
+    packages: &'a Packages,
+    address: &AccountAddress,
+    name: Symbol,
+) -> Option<&'a Module> {
+    packages
+        .packages
+        .get(address)
+        .and_then(|package| package.modules.get(&name))
+        .filter(|module| module.id.address == *address)
+        .or_else(|| {
+            packages
+                .packages
+                .values()
+                .flat_map(|package| package.modules.values())
+                .find(|module| module.id.address == *address && module.id.name == name)
+        })
+}
+
+fn check_type(
+    packages: &Packages,
+    location: &str,
+    type_: &Type,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    match type_ {
+        Type::Datatype(dt) => {
+            let found =
+                find_module(packages, &dt.module.address, dt.module.name).is_some_and(|module| {
+                    module.structs.contains_key(&dt.name) || module.enums.contains_key(&dt.name)
+                });
+            if !found {
+                issues.push(ValidationIssue::DanglingType {
+                    location: location.to_string(),
+                    address: dt.module.address,
+                    module: dt.module.name,
+                    name: dt.name,
+                });
+            }
+            for argument in &dt.type_arguments {
+                check_type(packages, location, &argument.argument, issues);
+            }
+        }
+        Type::Vector(inner) | Type::Reference(_, inner) => {
+            check_type(packages, location, inner, issues)
+        }
+        Type::Tuple(types) => {
+            for inner in types {
+                check_type(packages, location, inner, issues);
+            }
+        }
+        Type::Fun(args, ret) => {
+            for inner in args {
+                check_type(packages, location, inner, issues);
+            }
+            check_type(packages, location, ret, issues);
+        }
+        Type::Bool
+        | Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::U128
+        | Type::U256
+        | Type::Address
+        | Type::Signer
+        | Type::TypeParameter(_)
+        | Type::NamedTypeParameter(_)
+        | Type::Any => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pool_module, pools};
+    use move_model_2::summary::{Datatype, Field, ModuleId};
+
+    fn address(hex: &str) -> AccountAddress {
+        AccountAddress::from_hex_literal(hex).unwrap()
+    }
+
+    fn missing_balance() -> ValidationIssue {
+        ValidationIssue::MissingDependency {
+            module: "pools::pool".to_string(),
+            address: address("0x2"),
+            dependency: Symbol::from("balance"),
+        }
+    }
+
+    #[test]
+    fn reports_missing_dependencies() {
+        let issues = validate(&pools());
+        assert_eq!(issues, vec![missing_balance()]);
+        assert_eq!(
+            issues[0].to_string(),
+            format!(
+                "pools::pool depends on unknown module {}::balance",
+                address("0x2").to_hex_literal()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_dangling_types_inside_other_types() {
+        let mut packages = pools();
+        let package = packages.packages.values_mut().next().unwrap();
+        let pool = package.modules.values_mut().next().unwrap();
+        let balance = Type::Datatype(Box::new(Datatype {
+            module: ModuleId {
+                address: address("0x2"),
+                name: Symbol::from("balance"),
+            },
+            name: Symbol::from("Balance"),
+            type_arguments: Vec::new(),
+        }));
+        let field = Field {
+            index: 2,
+            doc: None,
+            type_: Type::Vector(Box::new(balance)),
+        };
+        let fields = &mut pool.structs.get_mut(&Symbol::from("Pool")).unwrap().fields;
+        fields.fields.insert(Symbol::from("balances"), field);
+
+        let issues = validate(&packages);
+        let dangling = ValidationIssue::DanglingType {
+            location: "pools::pool::Pool".to_string(),
+            address: address("0x2"),
+            module: Symbol::from("balance"),
+            name: Symbol::from("Balance"),
+        };
+        assert_eq!(issues, vec![missing_balance(), dangling]);
+        assert_eq!(
+            issues[1].to_string(),
+            format!(
+                "pools::pool::Pool references unknown type {}::balance::Balance",
+                address("0x2").to_hex_literal()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_modules_loaded_under_another_address() {
+        let mut packages = pools();
+        let package = packages.packages.pop_first().unwrap().1;
+        packages.packages.insert(address("0x3"), package);
+
+        // `Pool` still resolves through the module's own address
+        let issues = validate(&packages);
+        let mismatch = ValidationIssue::AddressMismatch {
+            module: "pools::pool".to_string(),
+            expected: address("0x3"),
+            found: pool_module().id.address,
+        };
+        assert_eq!(issues, vec![mismatch, missing_balance()]);
+        assert_eq!(
+            issues[0].to_string(),
+            format!(
+                "pools::pool has address {} but its package is mapped to {}",
+                address("0x2").to_hex_literal(),
+                address("0x3").to_hex_literal()
+            )
+        );
+    }
+}